@@ -0,0 +1,41 @@
+// cargo bench --bench msm
+// compares Straus (multi_scalar_mul) with Pippenger (multi_scalar_mul_pippenger) on secp256k1
+#![allow(non_snake_case)]
+
+use EC_ElGamalLike_Signature::{curves, EllipticCurve, Point};
+use num_bigint::{BigUint, RandBigInt};
+use std::time::{Duration, Instant};
+
+fn secp256k1() -> (EllipticCurve, Point, BigUint){
+    let secp256k1 = curves::secp256k1();
+    (secp256k1.curve, secp256k1.generator, secp256k1.order)
+}
+
+fn bench<F: FnMut() -> Point>(mut f: F) -> Duration{
+    let start = Instant::now();
+    std::hint::black_box(f());
+    start.elapsed()
+}
+
+fn main(){
+    let (ec, g, n) = secp256k1();
+    let mut rng = rand::thread_rng();
+
+    for size in [16usize, 64, 256, 1024] {
+        // G, 2G, 3G, ... are cheap to build and are as good as random bases here
+        let mut points = Vec::with_capacity(size);
+        let mut point = g.clone();
+        for _ in 0..size {
+            points.push(point.clone());
+            point = ec.add(&point, &g);
+        }
+        let scalars: Vec<BigUint> = (0..size)
+            .map(|_| rng.gen_biguint_range(&BigUint::from(1u32), &n))
+            .collect();
+
+        let straus = bench(|| ec.multi_scalar_mul(&points, &scalars));
+        let pippenger = bench(|| ec.multi_scalar_mul_pippenger(&points, &scalars));
+        let speedup = straus.as_secs_f64() / pippenger.as_secs_f64();
+        println!("n = {:<6} straus {:>12?}  pippenger {:>12?}  {:.2}x", size, straus, pippenger, speedup);
+    }
+}
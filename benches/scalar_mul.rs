@@ -0,0 +1,54 @@
+// cargo bench --bench scalar_mul
+// compares the plain double-and-add scalar_mul with w-NAF on secp256k1,
+// and the ladder with the precomputed generator table for secret scalars
+#![allow(non_snake_case)]
+
+use EC_ElGamalLike_Signature::signature::ElGamallikeSignature;
+use EC_ElGamalLike_Signature::{curves, EllipticCurve, Point, PrivateKey};
+use num_bigint::{BigUint, RandBigInt};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 50;
+
+fn secp256k1() -> (EllipticCurve, Point, BigUint){
+    let secp256k1 = curves::secp256k1();
+    (secp256k1.curve, secp256k1.generator, secp256k1.order)
+}
+
+fn bench<F: FnMut(&BigUint) -> Point>(scalars: &[BigUint], mut f: F) -> Duration{
+    let start = Instant::now();
+    for scalar in scalars {
+        std::hint::black_box(f(scalar));
+    }
+    start.elapsed() / scalars.len() as u32
+}
+
+fn main(){
+    let (ec, g, n) = secp256k1();
+    let mut rng = rand::thread_rng();
+    let scalars: Vec<BigUint> = (0..ITERATIONS)
+        .map(|_| rng.gen_biguint_range(&BigUint::from(1u32), &n))
+        .collect();
+
+    // a variable base, as in sR, rS and mB of the verifier
+    let base = ec.scalar_mul(&g, &rng.gen_biguint_range(&BigUint::from(1u32), &n));
+
+    let baseline = bench(&scalars, |k| ec.scalar_mul(&base, k));
+    println!("{:<24} {:>12?} / op", "scalar_mul", baseline);
+    for w in 2..=6 {
+        let elapsed = bench(&scalars, |k| ec.scalar_mul_wnaf(&base, k, w));
+        let speedup = baseline.as_secs_f64() / elapsed.as_secs_f64();
+        println!("{:<24} {:>12?} / op  {:.2}x", format!("scalar_mul_wnaf (w = {})", w), elapsed, speedup);
+    }
+
+    // secret scalars times the generator
+    let ladder = bench(&scalars, |k| ec.scalar_mul_ct(&g, k));
+    println!("{:<24} {:>12?} / op", "scalar_mul_ct", ladder);
+    let signature_scheme = ElGamallikeSignature::new(ec.clone(), g.clone(), n.clone());
+    let fixed_base = bench(&scalars, |k| {
+        let priv_key = PrivateKey::new(k.clone(), &signature_scheme).unwrap();
+        signature_scheme.generate_pub_key(&priv_key).point().clone()
+    });
+    let speedup = ladder.as_secs_f64() / fixed_base.as_secs_f64();
+    println!("{:<24} {:>12?} / op  {:.2}x", "generator table", fixed_base, speedup);
+}
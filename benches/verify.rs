@@ -0,0 +1,36 @@
+// cargo bench --bench verify
+// compares verifying signatures one by one with verify_batch on secp256k1
+#![allow(non_snake_case)]
+
+use EC_ElGamalLike_Signature::curves;
+use std::time::Instant;
+
+fn main(){
+    let signature_scheme = curves::secp256k1().signature_scheme();
+
+    for size in [16usize, 128] {
+        let mut items = Vec::with_capacity(size);
+        for _ in 0..size {
+            let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+            let hash = signature_scheme.generate_random_number_in_range(signature_scheme.order());
+            let k_random = signature_scheme.generate_nonce();
+            let l_random = signature_scheme.generate_nonce();
+            let signature = signature_scheme.sign(&hash, &priv_key, &k_random, &l_random).unwrap();
+            items.push((hash, pub_key, signature));
+        }
+        let batch: Vec<_> = items.iter().map(|(hash, pub_key, signature)| (hash, pub_key, signature)).collect();
+
+        let start = Instant::now();
+        for (hash, pub_key, signature) in &batch {
+            assert!(signature_scheme.verify(hash, pub_key, signature).unwrap());
+        }
+        let one_by_one = start.elapsed();
+
+        let start = Instant::now();
+        signature_scheme.verify_batch(&batch).unwrap();
+        let batched = start.elapsed();
+
+        let speedup = one_by_one.as_secs_f64() / batched.as_secs_f64();
+        println!("n = {:<6} verify {:>12?}  verify_batch {:>12?}  {:.2}x", size, one_by_one, batched, speedup);
+    }
+}
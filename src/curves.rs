@@ -0,0 +1,258 @@
+use crate::signature::ElGamallikeSignature;
+use crate::{EllipticCurve, Point};
+use num_bigint::BigUint;
+
+// NAMED CURVES
+// standard domain parameters, looked up by name or by object identifier:
+// secp256k1 and P-256/P-384/P-521 from SEC 2 and FIPS 186-4, brainpoolP256r1 from RFC 5639,
+// and the toy curve y^2 = x^3 + 6x + 2 mod 757 of the paper, which has no OID
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedCurve{
+    pub name: &'static str,
+    pub oid: Option<&'static str>,
+    pub curve: EllipticCurve,
+    pub generator: Point,
+    // order of the generator
+    pub order: BigUint,
+    // number of points on the curve divided by the order
+    pub cofactor: BigUint,
+}
+
+impl NamedCurve {
+    // the scheme over this curve, with the default SHA-256
+    pub fn signature_scheme(&self) -> ElGamallikeSignature{
+        ElGamallikeSignature::new(self.curve.clone(), self.generator.clone(), self.order.clone())
+    }
+}
+
+// the parameters in hex, parsed on lookup
+struct Parameters{
+    name: &'static str,
+    aliases: &'static [&'static str],
+    oid: Option<&'static str>,
+    p: &'static str,
+    a: &'static str,
+    b: &'static str,
+    gx: &'static str,
+    gy: &'static str,
+    n: &'static str,
+    h: u32,
+}
+
+const SECP256K1: Parameters = Parameters{
+    name: "secp256k1",
+    aliases: &[],
+    oid: Some("1.3.132.0.10"),
+    p: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+    a: "0",
+    b: "7",
+    gx: "79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+    gy: "483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
+    n: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+    h: 1,
+};
+
+const P256: Parameters = Parameters{
+    name: "P-256",
+    aliases: &["secp256r1", "prime256v1"],
+    oid: Some("1.2.840.10045.3.1.7"),
+    p: "FFFFFFFF00000001000000000000000000000000FFFFFFFFFFFFFFFFFFFFFFFF",
+    a: "FFFFFFFF00000001000000000000000000000000FFFFFFFFFFFFFFFFFFFFFFFC",
+    b: "5AC635D8AA3A93E7B3EBBD55769886BC651D06B0CC53B0F63BCE3C3E27D2604B",
+    gx: "6B17D1F2E12C4247F8BCE6E563A440F277037D812DEB33A0F4A13945D898C296",
+    gy: "4FE342E2FE1A7F9B8EE7EB4A7C0F9E162BCE33576B315ECECBB6406837BF51F5",
+    n: "FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551",
+    h: 1,
+};
+
+const P384: Parameters = Parameters{
+    name: "P-384",
+    aliases: &["secp384r1"],
+    oid: Some("1.3.132.0.34"),
+    p: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFF0000000000000000FFFFFFFF",
+    a: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFF0000000000000000FFFFFFFC",
+    b: "B3312FA7E23EE7E4988E056BE3F82D19181D9C6EFE8141120314088F5013875AC656398D8A2ED19D2A85C8EDD3EC2AEF",
+    gx: "AA87CA22BE8B05378EB1C71EF320AD746E1D3B628BA79B9859F741E082542A385502F25DBF55296C3A545E3872760AB7",
+    gy: "3617DE4A96262C6F5D9E98BF9292DC29F8F41DBD289A147CE9DA3113B5F0B8C00A60B1CE1D7E819D7A431D7C90EA0E5F",
+    n: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC7634D81F4372DDF581A0DB248B0A77AECEC196ACCC52973",
+    h: 1,
+};
+
+const P521: Parameters = Parameters{
+    name: "P-521",
+    aliases: &["secp521r1"],
+    oid: Some("1.3.132.0.35"),
+    p: "01FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+    a: "01FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC",
+    b: "0051953EB9618E1C9A1F929A21A0B68540EEA2DA725B99B315F3B8B489918EF109E156193951EC7E937B1652C0BD3BB1BF073573DF883D2C34F1EF451FD46B503F00",
+    gx: "00C6858E06B70404E9CD9E3ECB662395B4429C648139053FB521F828AF606B4D3DBAA14B5E77EFE75928FE1DC127A2FFA8DE3348B3C1856A429BF97E7E31C2E5BD66",
+    gy: "011839296A789A3BC0045C8A5FB42C7D1BD998F54449579B446817AFBD17273E662C97EE72995EF42640C550B9013FAD0761353C7086A272C24088BE94769FD16650",
+    n: "01FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFA51868783BF2F966B7FCC0148F709A5D03BB5C9B8899C47AEBB6FB71E91386409",
+    h: 1,
+};
+
+const BRAINPOOL_P256R1: Parameters = Parameters{
+    name: "brainpoolP256r1",
+    aliases: &[],
+    oid: Some("1.3.36.3.3.2.8.1.1.7"),
+    p: "A9FB57DBA1EEA9BC3E660A909D838D726E3BF623D52620282013481D1F6E5377",
+    a: "7D5A0975FC2C3057EEF67530417AFFE7FB8055C126DC5C6CE94A4B44F330B5D9",
+    b: "26DC5C6CE94A4B44F330B5D9BBD77CBF958416295CF7E1CE6BCCDC18FF8C07B6",
+    gx: "8BD2AEB9CB7E57CB2C4B482FFC81B7AFB9DE27E1E3BD23C23A4453BD9ACE3262",
+    gy: "547EF835C3DAC4FD97F8461A14611DC9C27745132DED8E545C1D54C72F046997",
+    n: "A9FB57DBA1EEA9BC3E660A909D838D718C397AA3B561A6F7901E0E82974856A7",
+    h: 1,
+};
+
+// https://arxiv.org/ftp/arxiv/papers/1301/1301.2335.pdf example 5.1.
+// G = (529, 566) has order 113 and the curve has 791 = 7 * 113 points
+const TOY757: Parameters = Parameters{
+    name: "toy757",
+    aliases: &[],
+    oid: None,
+    p: "2F5",
+    a: "6",
+    b: "2",
+    gx: "211",
+    gy: "236",
+    n: "71",
+    h: 7,
+};
+
+const CURVES: [&Parameters; 6] = [&SECP256K1, &P256, &P384, &P521, &BRAINPOOL_P256R1, &TOY757];
+
+fn parse(hex: &str) -> BigUint{
+    BigUint::parse_bytes(hex.as_bytes(), 16).expect("the registry holds valid hex")
+}
+
+impl Parameters {
+    fn named_curve(&self) -> NamedCurve{
+        NamedCurve{
+            name: self.name,
+            oid: self.oid,
+            curve: EllipticCurve{
+                a: parse(self.a),
+                b: parse(self.b),
+                p: parse(self.p),
+            },
+            generator: Point::Coor(parse(self.gx), parse(self.gy)),
+            order: parse(self.n),
+            cofactor: BigUint::from(self.h),
+        }
+    }
+}
+
+pub fn secp256k1() -> NamedCurve{
+    SECP256K1.named_curve()
+}
+
+pub fn p256() -> NamedCurve{
+    P256.named_curve()
+}
+
+pub fn p384() -> NamedCurve{
+    P384.named_curve()
+}
+
+pub fn p521() -> NamedCurve{
+    P521.named_curve()
+}
+
+pub fn brainpool_p256r1() -> NamedCurve{
+    BRAINPOOL_P256R1.named_curve()
+}
+
+pub fn toy757() -> NamedCurve{
+    TOY757.named_curve()
+}
+
+// every curve of the registry
+pub fn all() -> Vec<NamedCurve>{
+    CURVES.iter().map(|parameters| parameters.named_curve()).collect()
+}
+
+// the name or one of the SEC 2 / ANSI X9.62 aliases (secp256r1, prime256v1, ...), ignoring case
+pub fn by_name(name: &str) -> Option<NamedCurve>{
+    CURVES.iter()
+        .find(|parameters| std::iter::once(&parameters.name)
+            .chain(parameters.aliases.iter())
+            .any(|known| known.eq_ignore_ascii_case(name)))
+        .map(|parameters| parameters.named_curve())
+}
+
+// the OID in dotted form, e.g. "1.2.840.10045.3.1.7" for P-256
+pub fn by_oid(oid: &str) -> Option<NamedCurve>{
+    CURVES.iter()
+        .find(|parameters| parameters.oid == Some(oid))
+        .map(|parameters| parameters.named_curve())
+}
+
+// the registry curve with exactly these parameters, if any
+pub fn find(ec: &EllipticCurve, gen: &Point, q: &BigUint) -> Option<NamedCurve>{
+    CURVES.iter()
+        .filter(|parameters| parse(parameters.p) == ec.p)
+        .map(|parameters| parameters.named_curve())
+        .find(|curve| curve.curve == *ec && curve.generator == *gen && curve.order == *q)
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_named_curves(){
+        for curve in all() {
+            let ec = &curve.curve;
+            let gen = &curve.generator;
+            assert!(ec.is_on_curve(gen), "{}", curve.name);
+            assert_eq!(ec.scalar_mul(gen, &curve.order), Point::Identity, "{}", curve.name);
+            assert_ne!(ec.scalar_mul(gen, &(&curve.order - BigUint::from(1u32))), Point::Identity, "{}", curve.name);
+        }
+
+        assert_eq!(secp256k1().curve.p.bits(), 256);
+        assert_eq!(p384().order.bits(), 384);
+        assert_eq!(p521().curve.p, (BigUint::from(1u32) << 521) - BigUint::from(1u32));
+        assert_eq!(toy757().cofactor, BigUint::from(7u32));
+        assert_eq!(toy757().generator, Point::Coor(BigUint::from(529u32), BigUint::from(566u32)));
+    }
+
+    #[test]
+    fn test_named_curves_are_valid(){
+        for curve in all() {
+            // Hasse: the number of points n * h is within 2 sqrt(p) of p + 1
+            let points = &curve.order * &curve.cofactor;
+            let p_plus_one = &curve.curve.p + BigUint::from(1u32);
+            let distance = if points > p_plus_one { &points - &p_plus_one } else { &p_plus_one - &points };
+            assert!(&distance * &distance <= BigUint::from(4u32) * &curve.curve.p, "{}", curve.name);
+
+            let scheme = ElGamallikeSignature::try_new(curve.curve, curve.generator, curve.order);
+            assert!(scheme.is_ok(), "{}", curve.name);
+        }
+    }
+
+    #[test]
+    fn test_lookup(){
+        assert_eq!(by_name("secp256k1"), Some(secp256k1()));
+        assert_eq!(by_name("prime256v1"), Some(p256()));
+        assert_eq!(by_name("SECP384R1"), Some(p384()));
+        assert_eq!(by_name("p-521"), Some(p521()));
+        assert_eq!(by_name("curve25519"), None);
+
+        assert_eq!(by_oid("1.3.132.0.10"), Some(secp256k1()));
+        assert_eq!(by_oid("1.2.840.10045.3.1.7"), Some(p256()));
+        assert_eq!(by_oid("1.3.36.3.3.2.8.1.1.7"), Some(brainpool_p256r1()));
+        assert_eq!(by_oid("1.3.132.0"), None);
+        for curve in all() {
+            assert_eq!(by_name(curve.name).as_ref(), Some(&curve));
+            if let Some(oid) = curve.oid {
+                assert_eq!(by_oid(oid).as_ref(), Some(&curve));
+            }
+        }
+
+        let curve = brainpool_p256r1();
+        assert_eq!(find(&curve.curve, &curve.generator, &curve.order), Some(curve.clone()));
+        assert_eq!(find(&curve.curve, &curve.generator, &BigUint::from(7u32)), None);
+        let toy = toy757();
+        assert_eq!(find(&toy.curve, &toy.generator, &toy.order), Some(toy));
+    }
+}
@@ -0,0 +1,246 @@
+use crate::Error;
+use num_bigint::BigUint;
+
+// object identifier of the ElGamal-like scheme, in the 2.25 arc of ITU-T X.667 (an OID made of
+// a UUID, 26c0cce3-a0a4-4c64-9498-5f3405900277, which needs no registration)
+// it names the scheme both as a signature algorithm and as a public key algorithm
+pub const ELGAMAL_LIKE_OID: &str = "2.25.51511740482733751914860170995320226423";
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_BIT_STRING: u8 = 0x03;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+// [1] EXPLICIT, constructed context-specific tag
+pub(crate) const TAG_CONTEXT_1: u8 = 0xa1;
+
+// AlgorithmIdentifier ::= SEQUENCE { algorithm OBJECT IDENTIFIER }
+// the parameters are absent, as for Ed25519 in RFC 8410
+pub fn algorithm_identifier() -> Vec<u8>{
+    tlv(TAG_SEQUENCE, &tlv(TAG_OID, &oid(ELGAMAL_LIKE_OID)))
+}
+
+// Ok if the bytes are exactly algorithm_identifier()
+pub fn check_algorithm_identifier(bytes: &[u8]) -> Result<(), Error>{
+    let mut reader = DerReader::new(bytes);
+    let mut sequence = reader.read_sequence()?;
+    sequence.expect_oid(ELGAMAL_LIKE_OID)?;
+    sequence.finish()?;
+    reader.finish()
+}
+
+// DER (ITU-T X.690) encoding of tag || length || content, the length is always minimal
+pub(crate) fn tlv(tag: u8, content: &[u8]) -> Vec<u8>{
+    // allocated once, the content may be secret (PKCS#8) and must not be left behind by a regrowth
+    let mut out = Vec::with_capacity(2 + std::mem::size_of::<usize>() + content.len());
+    out.push(tag);
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|&&b| b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+// content of a non-negative INTEGER: minimal big-endian, with a leading 0x00 when the
+// top bit is set so that it does not read as negative
+pub(crate) fn integer(x: &BigUint) -> Vec<u8>{
+    let bytes = x.to_bytes_be();
+    let mut content = Vec::with_capacity(bytes.len() + 1);
+    if bytes[0] & 0x80 != 0 {
+        content.push(0);
+    }
+    content.extend(bytes);
+    tlv(TAG_INTEGER, &content)
+}
+
+// a BIT STRING of whole bytes, the first content byte counts the unused bits
+pub(crate) fn bit_string(bytes: &[u8]) -> Vec<u8>{
+    let mut content = Vec::with_capacity(bytes.len() + 1);
+    content.push(0);
+    content.extend_from_slice(bytes);
+    tlv(TAG_BIT_STRING, &content)
+}
+
+// content of an OBJECT IDENTIFIER given in dotted form, the first two arcs share one
+// subidentifier 40 * a + b and every subidentifier is base 128, high bit set on all bytes but the last
+pub(crate) fn oid(dotted: &str) -> Vec<u8>{
+    let arcs: Vec<BigUint> = dotted.split('.')
+        .map(|arc| arc.parse().expect("OID arcs are decimal numbers"))
+        .collect();
+    let first = &arcs[0] * BigUint::from(40u32) + &arcs[1];
+    let mut content = Vec::new();
+    for arc in std::iter::once(&first).chain(arcs[2..].iter()) {
+        let mut digits = arc.to_radix_le(128);
+        digits.reverse();
+        let last = digits.len() - 1;
+        for (i, digit) in digits.iter().enumerate() {
+            content.push(if i < last { digit | 0x80 } else { *digit });
+        }
+    }
+    content
+}
+
+// strict DER reader, rejects everything that BER allows but DER does not:
+// indefinite lengths, long form lengths below 128 or with leading zero bytes,
+// INTEGERs with redundant leading bytes, and trailing data
+pub(crate) struct DerReader<'a>{
+    data: &'a [u8],
+}
+
+impl<'a> DerReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self{
+        DerReader { data }
+    }
+
+    // content of the next element, which must have the given tag
+    pub(crate) fn read(&mut self, tag: u8) -> Result<&'a [u8], Error>{
+        let (&actual, rest) = self.data.split_first().ok_or(Error::InvalidEncoding)?;
+        if actual != tag {
+            return Err(Error::InvalidEncoding);
+        }
+        let (&first, mut rest) = rest.split_first().ok_or(Error::InvalidEncoding)?;
+        let len = if first < 0x80 {
+            first as usize
+        } else {
+            // 0x80 is the indefinite length of BER
+            let n = (first & 0x7f) as usize;
+            if n == 0 || n > std::mem::size_of::<usize>() || rest.len() < n || rest[0] == 0 {
+                return Err(Error::InvalidEncoding);
+            }
+            let len = rest[..n].iter().fold(0usize, |len, &b| (len << 8) | b as usize);
+            if len < 0x80 {
+                return Err(Error::InvalidEncoding);
+            }
+            rest = &rest[n..];
+            len
+        };
+        if rest.len() < len {
+            return Err(Error::InvalidEncoding);
+        }
+        let (content, rest) = rest.split_at(len);
+        self.data = rest;
+        Ok(content)
+    }
+
+    // a SEQUENCE, read its elements with the returned reader and finish it
+    pub(crate) fn read_sequence(&mut self) -> Result<DerReader<'a>, Error>{
+        Ok(DerReader::new(self.read(TAG_SEQUENCE)?))
+    }
+
+    // a non-negative INTEGER
+    pub(crate) fn read_integer(&mut self) -> Result<BigUint, Error>{
+        let content = self.read(TAG_INTEGER)?;
+        match content {
+            [] => Err(Error::InvalidEncoding),
+            [first, ..] if first & 0x80 != 0 => Err(Error::InvalidEncoding),
+            [0, second, ..] if second & 0x80 == 0 => Err(Error::InvalidEncoding),
+            _ => Ok(BigUint::from_bytes_be(content)),
+        }
+    }
+
+    // a BIT STRING of whole bytes, i.e. no unused bits
+    pub(crate) fn read_bit_string(&mut self) -> Result<&'a [u8], Error>{
+        match self.read(TAG_BIT_STRING)? {
+            [0, bytes @ ..] => Ok(bytes),
+            _ => Err(Error::InvalidEncoding),
+        }
+    }
+
+    // the next element if it has the given tag, for OPTIONAL fields
+    pub(crate) fn read_optional(&mut self, tag: u8) -> Result<Option<&'a [u8]>, Error>{
+        if self.data.first() != Some(&tag) {
+            return Ok(None);
+        }
+        self.read(tag).map(Some)
+    }
+
+    // an OBJECT IDENTIFIER that must be the given one
+    pub(crate) fn expect_oid(&mut self, dotted: &str) -> Result<(), Error>{
+        if self.read(TAG_OID)? != oid(dotted).as_slice() {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(())
+    }
+
+    // every byte has to be consumed
+    pub(crate) fn finish(self) -> Result<(), Error>{
+        if !self.data.is_empty() {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_der_encoding(){
+        assert_eq!(integer(&BigUint::from(0u32)), vec![0x02, 0x01, 0x00]);
+        assert_eq!(integer(&BigUint::from(127u32)), vec![0x02, 0x01, 0x7f]);
+        assert_eq!(integer(&BigUint::from(128u32)), vec![0x02, 0x02, 0x00, 0x80]);
+
+        let long = tlv(TAG_SEQUENCE, &[0u8; 200]);
+        assert_eq!(long[..3], [0x30, 0x81, 200]);
+        let longer = tlv(TAG_SEQUENCE, &[0u8; 300]);
+        assert_eq!(longer[..4], [0x30, 0x82, 0x01, 0x2c]);
+
+        // id-ecPublicKey, 1.2.840.10045.2.1
+        assert_eq!(oid("1.2.840.10045.2.1"), vec![0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01]);
+        // 2.25 arcs start with 40 * 2 + 25 = 105, the 126-bit UUID takes 18 bytes of 7 bits
+        let content = oid(ELGAMAL_LIKE_OID);
+        assert_eq!(content[0], 105);
+        assert_eq!(content.len(), 19);
+
+        assert_eq!(check_algorithm_identifier(&algorithm_identifier()), Ok(()));
+        // id-ecPublicKey instead
+        let ec_public_key = tlv(TAG_SEQUENCE, &tlv(TAG_OID, &oid("1.2.840.10045.2.1")));
+        assert_eq!(check_algorithm_identifier(&ec_public_key), Err(Error::InvalidEncoding));
+    }
+
+    #[test]
+    fn test_der_reader_is_strict(){
+        let read_integer = |bytes: &[u8]| -> Result<BigUint, Error> {
+            let mut reader = DerReader::new(bytes);
+            let x = reader.read_integer()?;
+            reader.finish()?;
+            Ok(x)
+        };
+
+        assert_eq!(read_integer(&[0x02, 0x02, 0x00, 0x80]), Ok(BigUint::from(128u32)));
+        // redundant leading zero
+        assert_eq!(read_integer(&[0x02, 0x02, 0x00, 0x7f]), Err(Error::InvalidEncoding));
+        // negative
+        assert_eq!(read_integer(&[0x02, 0x01, 0x80]), Err(Error::InvalidEncoding));
+        // empty
+        assert_eq!(read_integer(&[0x02, 0x00]), Err(Error::InvalidEncoding));
+        // long form for a short length
+        assert_eq!(read_integer(&[0x02, 0x81, 0x01, 0x05]), Err(Error::InvalidEncoding));
+        // long form with a leading zero byte
+        assert_eq!(read_integer(&[0x02, 0x82, 0x00, 0x01, 0x05]), Err(Error::InvalidEncoding));
+        // indefinite length
+        assert_eq!(read_integer(&[0x02, 0x80, 0x05, 0x00, 0x00]), Err(Error::InvalidEncoding));
+        // truncated
+        assert_eq!(read_integer(&[0x02, 0x02, 0x05]), Err(Error::InvalidEncoding));
+        // trailing data
+        assert_eq!(read_integer(&[0x02, 0x01, 0x05, 0x00]), Err(Error::InvalidEncoding));
+        // wrong tag
+        assert_eq!(read_integer(&[0x04, 0x01, 0x05]), Err(Error::InvalidEncoding));
+
+        let mut reader = DerReader::new(&[0x03, 0x02, 0x00, 0x05, 0x03, 0x02, 0x01, 0x04]);
+        assert_eq!(reader.read_optional(TAG_OCTET_STRING), Ok(None));
+        assert_eq!(reader.read_bit_string(), Ok(&[0x05u8][..]));
+        // one unused bit
+        assert_eq!(reader.read_bit_string(), Err(Error::InvalidEncoding));
+
+        let mut reader = DerReader::new(&[0x06, 0x03, 0x2a, 0x86, 0x48]);
+        assert_eq!(reader.expect_oid("1.2.840.10045.2.1"), Err(Error::InvalidEncoding));
+    }
+}
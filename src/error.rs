@@ -0,0 +1,105 @@
+use std::fmt;
+
+// crate-wide error type, every fallible operation returns Result<_, Error>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error{
+    // the point does not satisfy y^2 = x^3 + ax + b mod p
+    PointNotOnCurve,
+    // a scalar (hash, private key, nonce or t) is not in the expected range mod q
+    ScalarOutOfRange,
+    // a field element is not reduced mod p
+    FieldElementOutOfRange,
+    // the element has no multiplicative inverse mod p (it is 0 mod p)
+    NonInvertible,
+    // R or S of a signature is the identity point
+    IdentityInSignature,
+    // the public key is the identity point
+    IdentityPublicKey,
+    // the point is on the curve but not in the subgroup of order q generated by G
+    PointNotInSubgroup,
+    // the w-NAF window width is outside of the supported range
+    InvalidWindowSize,
+    // the slices of points and scalars of a multi-scalar multiplication have different lengths
+    LengthMismatch,
+    // indices of the signatures of a batch that are malformed or do not verify
+    InvalidSignatures(Vec<usize>),
+    // reading the input to sign or verify failed
+    Io(std::io::ErrorKind),
+    // the bytes are not a valid encoding (wrong tag, wrong length, ...)
+    InvalidEncoding,
+    // the encoded domain parameters are not those of the scheme
+    CurveMismatch,
+    // the keystore does not decrypt with this password (or it was modified)
+    WrongPassword,
+    // the curve or the domain parameters of the scheme fail a check, see ParameterError
+    InvalidParameters(ParameterError),
+}
+
+// the check of EllipticCurve::try_new or ElGamallikeSignature::try_new that failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterError{
+    // p is not a prime greater than 3
+    ModulusNotPrime,
+    // a or b is not reduced mod p
+    CoefficientOutOfRange,
+    // 4a^3 + 27b^2 = 0 mod p, the curve is singular
+    SingularCurve,
+    // the generator is not a point of the curve (off the curve or coordinates not reduced mod p)
+    GeneratorNotOnCurve,
+    // the generator is the identity point
+    IdentityGenerator,
+    // q is not prime
+    OrderNotPrime,
+    // qG is not the identity, q is not the order of the generator
+    WrongOrder,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self {
+            Error::PointNotOnCurve => f.write_str("point is not on the curve"),
+            Error::ScalarOutOfRange => f.write_str("scalar is out of range of the EC group"),
+            Error::FieldElementOutOfRange => f.write_str("field element is not reduced modulo p"),
+            Error::NonInvertible => f.write_str("element is not invertible modulo p"),
+            Error::IdentityInSignature => f.write_str("the signature points cannot be the identity"),
+            Error::IdentityPublicKey => f.write_str("the public key cannot be the identity"),
+            Error::PointNotInSubgroup => f.write_str("point is not in the subgroup generated by G"),
+            Error::InvalidWindowSize => f.write_str("window size is not supported"),
+            Error::LengthMismatch => f.write_str("points and scalars have different lengths"),
+            Error::InvalidSignatures(indices) => write!(f, "invalid signatures at indices {:?}", indices),
+            Error::Io(kind) => write!(f, "I/O error: {}", kind),
+            Error::InvalidEncoding => f.write_str("invalid encoding"),
+            Error::CurveMismatch => f.write_str("the key belongs to another curve"),
+            Error::WrongPassword => f.write_str("wrong password or corrupted keystore"),
+            Error::InvalidParameters(e) => write!(f, "invalid domain parameters: {}", e),
+        }
+    }
+}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self {
+            ParameterError::ModulusNotPrime => f.write_str("p is not a prime greater than 3"),
+            ParameterError::CoefficientOutOfRange => f.write_str("a or b is not reduced modulo p"),
+            ParameterError::SingularCurve => f.write_str("the curve is singular, 4a^3 + 27b^2 = 0 mod p"),
+            ParameterError::GeneratorNotOnCurve => f.write_str("the generator is not on the curve"),
+            ParameterError::IdentityGenerator => f.write_str("the generator cannot be the identity"),
+            ParameterError::OrderNotPrime => f.write_str("the order q is not prime"),
+            ParameterError::WrongOrder => f.write_str("q is not the order of the generator"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParameterError> for Error {
+    fn from(e: ParameterError) -> Self{
+        Error::InvalidParameters(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self{
+        Error::Io(e.kind())
+    }
+}
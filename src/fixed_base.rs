@@ -0,0 +1,133 @@
+use crate::jacobian::JacobianPoint;
+use crate::{EllipticCurve, Point};
+use num_bigint::BigUint;
+
+// window width of the generator table, 2^4 points per window
+const WINDOW: usize = 4;
+
+// fixed-window table for a base point that is known in advance (the generator)
+// rows[i][j] = (j + 1) * 2^(WINDOW * i) * base, every window of k adds the entry of its digit
+// plus one, so no selected entry is the identity (adding the identity returns early and would
+// reveal the zero digits), and the sum is k * base + sum_i 2^(WINDOW * i) * base.
+// offset is the negation of that constant and is added at the end. No doubling at all is needed.
+pub(crate) struct FixedBaseTable{
+    base: JacobianPoint,
+    rows: Vec<Vec<JacobianPoint>>,
+    offset: JacobianPoint,
+}
+
+impl FixedBaseTable {
+    // covers every scalar of at most p.bits() + 1 bits, i.e. every scalar below the group order
+    pub(crate) fn new(ec: &EllipticCurve, base: &Point) -> Self{
+        let windows = (ec.p.bits() as usize + 1).div_ceil(WINDOW);
+        let base = ec.to_jacobian(base);
+        let mut rows = Vec::with_capacity(windows);
+        let mut offset = JacobianPoint::identity();
+        let mut window_base = base.clone();
+        for _ in 0..windows {
+            offset = ec.jacobian_add(&offset, &window_base);
+            let mut row = Vec::with_capacity(1 << WINDOW);
+            row.push(window_base.clone());
+            for j in 1..(1 << WINDOW) {
+                let next = ec.jacobian_add(&row[j - 1], &window_base);
+                row.push(next);
+            }
+            // 2^WINDOW * window_base
+            window_base = row[(1 << WINDOW) - 1].clone();
+            rows.push(row);
+        }
+        offset.y = (&ec.p - &offset.y) % &ec.p;
+        FixedBaseTable { base, rows, offset }
+    }
+
+    fn capacity_bits(&self) -> u64{
+        (self.rows.len() * WINDOW) as u64
+    }
+
+    // the bits are shifted in without branching on them, the exponent is secret
+    fn window_digit(exponent: &BigUint, i: usize) -> usize{
+        let mut digit = 0;
+        for b in 0..WINDOW {
+            digit |= (exponent.bit((i * WINDOW + b) as u64) as usize) << b;
+        }
+        digit
+    }
+
+    // every entry of a row is read and selected with a mask, so the memory access pattern
+    // does not depend on the secret digit, and every window costs one addition of two points
+    // that are not the identity (except when a partial sum happens to be a multiple of the
+    // order, which a random scalar hits with negligible probability)
+    pub(crate) fn mul(&self, ec: &EllipticCurve, exponent: &BigUint) -> JacobianPoint{
+        if exponent.bits() > self.capacity_bits() {
+            // only happens for scalars bigger than the group order
+            return ec.scalar_mul_ladder(&self.base, exponent);
+        }
+        if self.base.is_identity() {
+            return JacobianPoint::identity();
+        }
+        let width = ec.p.to_u32_digits().len();
+        let mut t: Option<JacobianPoint> = None;
+        for (i, row) in self.rows.iter().enumerate() {
+            let digit = Self::window_digit(exponent, i);
+            let mut selected = row[0].clone();
+            for (j, entry) in row.iter().enumerate() {
+                selected.conditional_assign(entry, j == digit, width);
+            }
+            t = Some(match t {
+                Some(t) => ec.jacobian_add(&t, &selected),
+                None => selected,
+            });
+        }
+        ec.jacobian_add(&t.expect("the table has at least one row"), &self.offset)
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+    use num_bigint::RandBigInt;
+
+    #[test]
+    fn test_fixed_base_table_toy_curve(){
+        //y^2 = x^3 + 2x + 2 mod 17, the order of the group is 19
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let g = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+        let table = FixedBaseTable::new(&ec, &g);
+
+        for k in 0u32..100 {
+            let k = BigUint::from(k);
+            assert_eq!(ec.to_affine(&table.mul(&ec, &k)), ec.scalar_mul(&g, &k));
+        }
+    }
+
+    #[test]
+    fn test_fixed_base_table_secp256k1(){
+        let secp256k1 = crate::curves::secp256k1();
+        let ec = secp256k1.curve;
+        let n = secp256k1.order;
+        let g = secp256k1.generator;
+        let table = FixedBaseTable::new(&ec, &g);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..5 {
+            let k = rng.gen_biguint_range(&BigUint::from(1u32), &n);
+            assert_eq!(ec.to_affine(&table.mul(&ec, &k)), ec.scalar_mul(&g, &k));
+        }
+        assert!(table.mul(&ec, &n).is_identity());
+
+        // zero digits select (0 + 1) * 2^(4i) * G like any other digit, no entry is the identity
+        assert!(table.rows.iter().flatten().all(|entry| !entry.is_identity()));
+        for k in [BigUint::from(0u32), BigUint::from(1u32), BigUint::from(1u32) << 100, &n - BigUint::from(1u32)] {
+            assert_eq!(ec.to_affine(&table.mul(&ec, &k)), ec.scalar_mul(&g, &k));
+        }
+
+        // scalars that do not fit in the table fall back to the ladder
+        let big = &n * &n + BigUint::from(3u32);
+        assert_eq!(ec.to_affine(&table.mul(&ec, &big)), ec.scalar_mul(&g, &BigUint::from(3u32)));
+    }
+}
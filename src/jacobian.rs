@@ -0,0 +1,328 @@
+use crate::{EllipticCurve, FiniteField, Point};
+use num_bigint::BigUint;
+
+// Jacobian coordinates: (X, Y, Z) represents the affine point (X/Z^2, Y/Z^3)
+// any point with Z = 0 is the identity
+// add and double never invert, the single inversion happens in to_affine
+#[derive(Clone, Debug)]
+pub(crate) struct JacobianPoint{
+    pub(crate) x: BigUint,
+    pub(crate) y: BigUint,
+    pub(crate) z: BigUint,
+}
+
+impl JacobianPoint {
+    pub(crate) fn identity() -> Self{
+        JacobianPoint {
+            x: BigUint::from(1u32),
+            y: BigUint::from(1u32),
+            z: BigUint::from(0u32),
+        }
+    }
+
+    pub(crate) fn is_identity(&self) -> bool{
+        self.z == BigUint::from(0u32)
+    }
+
+    // swaps self and other when swap is true without branching on swap
+    // every coordinate is padded to width digits so the work does not depend on the values
+    pub(crate) fn conditional_swap(&mut self, other: &mut JacobianPoint, swap: bool, width: usize){
+        conditional_swap(&mut self.x, &mut other.x, swap, width);
+        conditional_swap(&mut self.y, &mut other.y, swap, width);
+        conditional_swap(&mut self.z, &mut other.z, swap, width);
+    }
+
+    // self = other when assign is true, with the same padding as conditional_swap
+    pub(crate) fn conditional_assign(&mut self, other: &JacobianPoint, assign: bool, width: usize){
+        conditional_assign(&mut self.x, &other.x, assign, width);
+        conditional_assign(&mut self.y, &other.y, assign, width);
+        conditional_assign(&mut self.z, &other.z, assign, width);
+    }
+}
+
+fn conditional_assign(c: &mut BigUint, d: &BigUint, assign: bool, width: usize){
+    let mask = 0u32.wrapping_sub(assign as u32);
+    let mut c_digits = c.to_u32_digits();
+    let mut d_digits = d.to_u32_digits();
+    c_digits.resize(width, 0);
+    d_digits.resize(width, 0);
+    for (c_digit, d_digit) in c_digits.iter_mut().zip(d_digits.iter()) {
+        *c_digit ^= mask & (*c_digit ^ *d_digit);
+    }
+    *c = BigUint::from_slice(&c_digits);
+}
+
+fn conditional_swap(c: &mut BigUint, d: &mut BigUint, swap: bool, width: usize){
+    let mask = 0u32.wrapping_sub(swap as u32);
+    let mut c_digits = c.to_u32_digits();
+    let mut d_digits = d.to_u32_digits();
+    c_digits.resize(width, 0);
+    d_digits.resize(width, 0);
+    for (c_digit, d_digit) in c_digits.iter_mut().zip(d_digits.iter_mut()) {
+        let t = mask & (*c_digit ^ *d_digit);
+        *c_digit ^= t;
+        *d_digit ^= t;
+    }
+    *c = BigUint::from_slice(&c_digits);
+    *d = BigUint::from_slice(&d_digits);
+}
+
+impl EllipticCurve {
+    pub(crate) fn to_jacobian(&self, c: &Point) -> JacobianPoint{
+        match c {
+            Point::Identity => JacobianPoint::identity(),
+            Point::Coor(x, y) => JacobianPoint {
+                x: x.clone(),
+                y: y.clone(),
+                z: BigUint::from(1u32),
+            },
+        }
+    }
+
+    pub(crate) fn to_affine(&self, c: &JacobianPoint) -> Point{
+        if c.is_identity() {
+            return Point::Identity;
+        }
+        // x = X / Z^2, y = Y / Z^3
+        let z_inv = FiniteField::inv_mul(&c.z, &self.p);
+        let z_inv_square = FiniteField::mul(&z_inv, &z_inv, &self.p);
+        let z_inv_cube = FiniteField::mul(&z_inv_square, &z_inv, &self.p);
+        let x = FiniteField::mul(&c.x, &z_inv_square, &self.p);
+        let y = FiniteField::mul(&c.y, &z_inv_cube, &self.p);
+        Point::Coor(x, y)
+    }
+
+    pub(crate) fn jacobian_double(&self, c: &JacobianPoint) -> JacobianPoint{
+        if c.is_identity() || c.y == BigUint::from(0u32) {
+            return JacobianPoint::identity();
+        }
+        // https://hyperelliptic.org/EFD/g1p/auto-shortw-jacobian.html#doubling-dbl-1998-cmo-2
+        // S = 4 * X1 * Y1^2
+        // M = 3 * X1^2 + a * Z1^4
+        // X3 = M^2 - 2 * S
+        // Y3 = M * (S - X3) - 8 * Y1^4
+        // Z3 = 2 * Y1 * Z1
+        let y1_square = FiniteField::mul(&c.y, &c.y, &self.p);
+        let x1_y1_square = FiniteField::mul(&c.x, &y1_square, &self.p);
+        let s = FiniteField::mul(&x1_y1_square, &BigUint::from(4u32), &self.p);
+        let x1_square = FiniteField::mul(&c.x, &c.x, &self.p);
+        let x1_square_3x = FiniteField::mul(&x1_square, &BigUint::from(3u32), &self.p);
+        let z1_square = FiniteField::mul(&c.z, &c.z, &self.p);
+        let z1_fourth = FiniteField::mul(&z1_square, &z1_square, &self.p);
+        let a_z1_fourth = FiniteField::mul(&self.a, &z1_fourth, &self.p);
+        let m = FiniteField::add(&x1_square_3x, &a_z1_fourth, &self.p);
+        let m_square = FiniteField::mul(&m, &m, &self.p);
+        let s_mul_2 = FiniteField::mul(&s, &BigUint::from(2u32), &self.p);
+        let x3 = FiniteField::subs(&m_square, &s_mul_2, &self.p);
+        let s_minus_x3 = FiniteField::subs(&s, &x3, &self.p);
+        let y1_fourth = FiniteField::mul(&y1_square, &y1_square, &self.p);
+        let y1_fourth_8x = FiniteField::mul(&y1_fourth, &BigUint::from(8u32), &self.p);
+        let m_s_minus_x3 = FiniteField::mul(&m, &s_minus_x3, &self.p);
+        let y3 = FiniteField::subs(&m_s_minus_x3, &y1_fourth_8x, &self.p);
+        let y1_z1 = FiniteField::mul(&c.y, &c.z, &self.p);
+        let z3 = FiniteField::mul(&y1_z1, &BigUint::from(2u32), &self.p);
+        JacobianPoint { x: x3, y: y3, z: z3 }
+    }
+
+    pub(crate) fn jacobian_add(&self, c: &JacobianPoint, d: &JacobianPoint) -> JacobianPoint{
+        if c.is_identity() {
+            return d.clone();
+        }
+        if d.is_identity() {
+            return c.clone();
+        }
+        // https://hyperelliptic.org/EFD/g1p/auto-shortw-jacobian.html#addition-add-1998-cmo-2
+        // U1 = X1 * Z2^2, U2 = X2 * Z1^2
+        // S1 = Y1 * Z2^3, S2 = Y2 * Z1^3
+        // H = U2 - U1, r = S2 - S1
+        // X3 = r^2 - H^3 - 2 * U1 * H^2
+        // Y3 = r * (U1 * H^2 - X3) - S1 * H^3
+        // Z3 = Z1 * Z2 * H
+        let z1_square = FiniteField::mul(&c.z, &c.z, &self.p);
+        let z2_square = FiniteField::mul(&d.z, &d.z, &self.p);
+        let u1 = FiniteField::mul(&c.x, &z2_square, &self.p);
+        let u2 = FiniteField::mul(&d.x, &z1_square, &self.p);
+        let z1_cube = FiniteField::mul(&z1_square, &c.z, &self.p);
+        let z2_cube = FiniteField::mul(&z2_square, &d.z, &self.p);
+        let s1 = FiniteField::mul(&c.y, &z2_cube, &self.p);
+        let s2 = FiniteField::mul(&d.y, &z1_cube, &self.p);
+        let h = FiniteField::subs(&u2, &u1, &self.p);
+        let r = FiniteField::subs(&s2, &s1, &self.p);
+        if h == BigUint::from(0u32) {
+            // same x coordinate: either P + P or P + (-P)
+            if r == BigUint::from(0u32) {
+                return self.jacobian_double(c);
+            }
+            return JacobianPoint::identity();
+        }
+        let h_square = FiniteField::mul(&h, &h, &self.p);
+        let h_cube = FiniteField::mul(&h_square, &h, &self.p);
+        let v = FiniteField::mul(&u1, &h_square, &self.p);
+        let r_square = FiniteField::mul(&r, &r, &self.p);
+        let v_mul_2 = FiniteField::mul(&v, &BigUint::from(2u32), &self.p);
+        let mut x3 = FiniteField::subs(&r_square, &h_cube, &self.p);
+        x3 = FiniteField::subs(&x3, &v_mul_2, &self.p);
+        let v_minus_x3 = FiniteField::subs(&v, &x3, &self.p);
+        let r_v_minus_x3 = FiniteField::mul(&r, &v_minus_x3, &self.p);
+        let s1_h_cube = FiniteField::mul(&s1, &h_cube, &self.p);
+        let y3 = FiniteField::subs(&r_v_minus_x3, &s1_h_cube, &self.p);
+        let z1_z2 = FiniteField::mul(&c.z, &d.z, &self.p);
+        let z3 = FiniteField::mul(&z1_z2, &h, &self.p);
+        JacobianPoint { x: x3, y: y3, z: z3 }
+    }
+
+    // left-to-right double-and-add entirely in Jacobian coordinates
+    // starts from the identity so 0 * c = identity, and since the addition law is complete
+    // any scalar (including multiples of the order and scalars bigger than the order) is fine
+    // the caller is responsible for checking that c is on the curve
+    pub(crate) fn scalar_mul_jacobian(&self, c: &JacobianPoint, exponent: &BigUint) -> JacobianPoint{
+        let mut t = JacobianPoint::identity();
+        for i in (0..exponent.bits()).rev(){
+            t = self.jacobian_double(&t);
+            if exponent.bit(i){
+                t = self.jacobian_add(&t, c);
+            }
+        }
+        t
+    }
+
+    // Montgomery ladder for secret scalars
+    // invariant: R1 - R0 = c, every bit costs exactly one addition and one doubling
+    // and the operands are selected with conditional_swap instead of a branch on the bit.
+    // The number of iterations is fixed by the field size (the group order is at most p + 1 + 2 sqrt(p)).
+    // The ladder runs on k + 2^bits, whose top bit is always set, and starts from R0 = c, R1 = 2c
+    // at that bit: starting from the identity would make the leading zero bits of k almost free
+    // (add and double return early on the identity) and reveal its bit length.
+    // 2^bits * c only depends on c and is subtracted at the end.
+    // BigUint arithmetic itself is not constant time, this only removes the secret dependent
+    // sequence of group operations that the double-and-add path exposes.
+    pub(crate) fn scalar_mul_ladder(&self, c: &JacobianPoint, exponent: &BigUint) -> JacobianPoint{
+        if c.is_identity() {
+            return JacobianPoint::identity();
+        }
+        let width = self.p.to_u32_digits().len();
+        let bits = std::cmp::max(self.p.bits() + 1, exponent.bits());
+        let mut r0 = c.clone();
+        let mut r1 = self.jacobian_double(c);
+        for i in (0..bits).rev(){
+            let bit = exponent.bit(i);
+            r0.conditional_swap(&mut r1, bit, width);
+            r1 = self.jacobian_add(&r0, &r1);
+            r0 = self.jacobian_double(&r0);
+            r0.conditional_swap(&mut r1, bit, width);
+        }
+
+        let mut offset = c.clone();
+        for _ in 0..bits {
+            offset = self.jacobian_double(&offset);
+        }
+        offset.y = (&self.p - &offset.y) % &self.p;
+        self.jacobian_add(&r0, &offset)
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_jacobian_matches_affine(){
+        //y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let p = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+        let q = Point::Coor(BigUint::from(3u32), BigUint::from(16u32));
+        let p_jacobian = ec.to_jacobian(&p);
+        let q_jacobian = ec.to_jacobian(&q);
+
+        let sum = ec.jacobian_add(&p_jacobian, &q_jacobian);
+        assert_eq!(ec.to_affine(&sum), ec.add(&p, &q));
+
+        let two_p = ec.jacobian_double(&p_jacobian);
+        assert_eq!(ec.to_affine(&two_p), ec.double(&p));
+
+        // (2P) + P is computed with Z != 1 on the left hand side
+        let three_p = ec.jacobian_add(&two_p, &p_jacobian);
+        assert_eq!(ec.to_affine(&three_p), ec.add(&ec.double(&p), &p));
+
+        // P + P in the addition formula falls back to doubling
+        assert_eq!(ec.to_affine(&ec.jacobian_add(&two_p, &two_p)), ec.to_affine(&ec.jacobian_double(&two_p)));
+
+        // P + (-P) = identity
+        let minus_p = ec.to_jacobian(&ec.negate(&p));
+        assert!(ec.jacobian_add(&p_jacobian, &minus_p).is_identity());
+    }
+
+    #[test]
+    fn test_scalar_mul_ladder(){
+        //y^2 = x^3 + 2x + 2 mod 17, the order of the group is 19
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let p = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+        let p_jacobian = ec.to_jacobian(&p);
+
+        for k in 1u32..19 {
+            let k = BigUint::from(k);
+            let ladder = ec.scalar_mul_ladder(&p_jacobian, &k);
+            let double_and_add = ec.scalar_mul_jacobian(&p_jacobian, &k);
+            assert_eq!(ec.to_affine(&ladder), ec.to_affine(&double_and_add));
+        }
+
+        // scalars of every bit length, and bigger than the field
+        for k in [0u32, 1, 2, 3, 38, 1000, 1 << 20, u32::MAX] {
+            let k = BigUint::from(k);
+            let ladder = ec.scalar_mul_ladder(&p_jacobian, &k);
+            assert_eq!(ec.to_affine(&ladder), ec.scalar_mul(&p, &k));
+        }
+
+        //y^2 = x^3 + 4x mod 5, (0, 0) has order 2 so 2c is the identity from the start
+        let two_torsion = EllipticCurve{
+            a: BigUint::from(4u32),
+            b: BigUint::from(0u32),
+            p: BigUint::from(5u32),
+        };
+        let t = Point::Coor(BigUint::from(0u32), BigUint::from(0u32));
+        assert!(two_torsion.is_on_curve(&t));
+        let t_jacobian = two_torsion.to_jacobian(&t);
+        assert_eq!(two_torsion.to_affine(&two_torsion.scalar_mul_ladder(&t_jacobian, &BigUint::from(5u32))), t);
+        assert!(two_torsion.scalar_mul_ladder(&t_jacobian, &BigUint::from(6u32)).is_identity());
+
+        assert!(ec.scalar_mul_ladder(&p_jacobian, &BigUint::from(0u32)).is_identity());
+        assert!(ec.scalar_mul_ladder(&p_jacobian, &BigUint::from(19u32)).is_identity());
+        assert!(ec.scalar_mul_ladder(&JacobianPoint::identity(), &BigUint::from(7u32)).is_identity());
+    }
+
+    #[test]
+    fn test_conditional_swap(){
+        let mut c = JacobianPoint { x: BigUint::from(1u32), y: BigUint::from(2u32), z: BigUint::from(3u32) };
+        let mut d = JacobianPoint::identity();
+
+        c.conditional_swap(&mut d, false, 1);
+        assert_eq!(c.z, BigUint::from(3u32));
+        assert!(d.is_identity());
+
+        c.conditional_swap(&mut d, true, 1);
+        assert!(c.is_identity());
+        assert_eq!((d.x, d.y, d.z), (BigUint::from(1u32), BigUint::from(2u32), BigUint::from(3u32)));
+    }
+
+    #[test]
+    fn test_conditional_assign(){
+        let mut c = JacobianPoint::identity();
+        let d = JacobianPoint { x: BigUint::from(1u32), y: BigUint::from(2u32), z: BigUint::from(3u32) };
+
+        c.conditional_assign(&d, false, 1);
+        assert!(c.is_identity());
+
+        c.conditional_assign(&d, true, 1);
+        assert_eq!((c.x, c.y, c.z), (BigUint::from(1u32), BigUint::from(2u32), BigUint::from(3u32)));
+    }
+}
@@ -0,0 +1,408 @@
+use crate::der::{self, DerReader};
+use crate::nonce::int2octets;
+use crate::secret::wipe;
+use crate::signature::ElGamallikeSignature;
+use crate::{Error, Point};
+use num_bigint::BigUint;
+use std::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+// the private scalar a, always in [1, q)
+// it is wiped on drop (see secret::wipe) and never shown by Debug
+// there is no PartialEq, comparing BigUints stops at the first differing digit
+#[derive(Clone)]
+pub struct PrivateKey{
+    pub(crate) scalar: BigUint,
+}
+
+impl PrivateKey {
+    pub fn new<D>(scalar: BigUint, signature_scheme: &ElGamallikeSignature<D>) -> Result<Self, Error>{
+        signature_scheme.check_nonzero_scalar(&scalar)?;
+        Ok(PrivateKey { scalar })
+    }
+
+    pub fn scalar(&self) -> &BigUint{
+        &self.scalar
+    }
+
+    // B = aG
+    pub fn public_key<D>(&self, signature_scheme: &ElGamallikeSignature<D>) -> PublicKey{
+        signature_scheme.generate_pub_key(self)
+    }
+}
+
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        f.write_str("PrivateKey(<redacted>)")
+    }
+}
+
+impl Zeroize for PrivateKey {
+    fn zeroize(&mut self){
+        wipe(&mut self.scalar);
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self){
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PrivateKey {}
+
+// a nonce k or l of sign, a secret scalar in [1, q)
+// it has its own type so that it cannot be passed in place of the hash (or the other way around)
+// like PrivateKey it is wiped on drop and never shown by Debug
+#[derive(Clone)]
+pub struct Nonce{
+    pub(crate) scalar: BigUint,
+}
+
+impl Nonce {
+    pub fn new<D>(scalar: BigUint, signature_scheme: &ElGamallikeSignature<D>) -> Result<Self, Error>{
+        signature_scheme.check_nonzero_scalar(&scalar)?;
+        Ok(Nonce { scalar })
+    }
+}
+
+impl fmt::Debug for Nonce {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        f.write_str("Nonce(<redacted>)")
+    }
+}
+
+impl Zeroize for Nonce {
+    fn zeroize(&mut self){
+        wipe(&mut self.scalar);
+    }
+}
+
+impl Drop for Nonce {
+    fn drop(&mut self){
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Nonce {}
+
+// the public point B = aG, always on the curve and never the identity
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey{
+    pub(crate) point: Point,
+}
+
+impl PublicKey {
+    pub fn new<D>(point: Point, signature_scheme: &ElGamallikeSignature<D>) -> Result<Self, Error>{
+        signature_scheme.check_public_key(&point)?;
+        Ok(PublicKey { point })
+    }
+
+    pub fn point(&self) -> &Point{
+        &self.point
+    }
+
+    pub fn verify<D>(
+        &self,
+        signature_scheme: &ElGamallikeSignature<D>,
+        hash: &BigUint,
+        signature: &Signature
+    ) -> Result<bool, Error> {
+        signature_scheme.verify(hash, self, signature)
+    }
+}
+
+// the signature (R, S, t), R and S are on the curve and not the identity, t is in [0, q)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature{
+    pub(crate) r_point: Point,
+    pub(crate) s_point: Point,
+    pub(crate) t: BigUint,
+}
+
+impl Signature {
+    pub fn new<D>(
+        r_point: Point,
+        s_point: Point,
+        t: BigUint,
+        signature_scheme: &ElGamallikeSignature<D>
+    ) -> Result<Self, Error> {
+        signature_scheme.check_signature_parts(&r_point, &s_point, &t)?;
+        Ok(Signature { r_point, s_point, t })
+    }
+
+    pub fn r_point(&self) -> &Point{
+        &self.r_point
+    }
+
+    pub fn s_point(&self) -> &Point{
+        &self.s_point
+    }
+
+    pub fn t(&self) -> &BigUint{
+        &self.t
+    }
+
+    // compact encoding, fixed width for a given scheme:
+    // compressed R (1 + field_size bytes) || compressed S (1 + field_size bytes) || t (big-endian)
+    // t takes as many bytes as the order q
+    pub fn size<D>(signature_scheme: &ElGamallikeSignature<D>) -> usize{
+        2 * (1 + signature_scheme.curve().field_size()) + scalar_size(signature_scheme.order())
+    }
+
+    pub fn to_bytes<D>(&self, signature_scheme: &ElGamallikeSignature<D>) -> Vec<u8>{
+        let ec = signature_scheme.curve();
+        let mut out = Vec::with_capacity(Self::size(signature_scheme));
+        out.extend(ec.encode_point(&self.r_point, true));
+        out.extend(ec.encode_point(&self.s_point, true));
+        out.extend(int2octets(&self.t, scalar_size(signature_scheme.order())));
+        out
+    }
+
+    // strict parser, every signature has exactly one accepted encoding: the length must be
+    // exact, R and S must be compressed (no identity, no uncompressed form) with x < p,
+    // and t < q
+    pub fn from_bytes<D>(bytes: &[u8], signature_scheme: &ElGamallikeSignature<D>) -> Result<Self, Error>{
+        if bytes.len() != Self::size(signature_scheme) {
+            return Err(Error::InvalidEncoding);
+        }
+        let ec = signature_scheme.curve();
+        let point_size = 1 + ec.field_size();
+        let (r_bytes, rest) = bytes.split_at(point_size);
+        let (s_bytes, t_bytes) = rest.split_at(point_size);
+        if !is_compressed_tag(r_bytes[0]) || !is_compressed_tag(s_bytes[0]) {
+            return Err(Error::InvalidEncoding);
+        }
+        let r_point = ec.decode_point(r_bytes)?;
+        let s_point = ec.decode_point(s_bytes)?;
+        let t = BigUint::from_bytes_be(t_bytes);
+        Signature::new(r_point, s_point, t, signature_scheme)
+    }
+}
+
+// DER encoding, see der.rs
+// ElGamalLikeSignatureValue ::= SEQUENCE {
+//     R  ECPointCoordinates,
+//     S  ECPointCoordinates,
+//     t  INTEGER
+// }
+// ECPointCoordinates ::= SEQUENCE { x INTEGER, y INTEGER }
+// the scheme itself is named by der::ELGAMAL_LIKE_OID (der::algorithm_identifier)
+impl Signature {
+    pub fn to_der(&self) -> Vec<u8>{
+        let mut content = point_to_der(&self.r_point);
+        content.extend(point_to_der(&self.s_point));
+        content.extend(der::integer(&self.t));
+        der::tlv(der::TAG_SEQUENCE, &content)
+    }
+
+    // strict DER only, the result is checked like Signature::new
+    pub fn from_der<D>(bytes: &[u8], signature_scheme: &ElGamallikeSignature<D>) -> Result<Self, Error>{
+        let mut reader = DerReader::new(bytes);
+        let mut sequence = reader.read_sequence()?;
+        let r_point = point_from_der(&mut sequence)?;
+        let s_point = point_from_der(&mut sequence)?;
+        let t = sequence.read_integer()?;
+        sequence.finish()?;
+        reader.finish()?;
+        Signature::new(r_point, s_point, t, signature_scheme)
+    }
+}
+
+// the identity has no coordinates, it never occurs in a valid signature
+fn point_to_der(c: &Point) -> Vec<u8>{
+    match c {
+        Point::Coor(x, y) => {
+            let mut content = der::integer(x);
+            content.extend(der::integer(y));
+            der::tlv(der::TAG_SEQUENCE, &content)
+        }
+        Point::Identity => der::tlv(der::TAG_SEQUENCE, &[]),
+    }
+}
+
+fn point_from_der(reader: &mut DerReader) -> Result<Point, Error>{
+    let mut sequence = reader.read_sequence()?;
+    let x = sequence.read_integer()?;
+    let y = sequence.read_integer()?;
+    sequence.finish()?;
+    Ok(Point::Coor(x, y))
+}
+
+pub(crate) fn scalar_size(q: &BigUint) -> usize{
+    (q.bits() as usize).div_ceil(8)
+}
+
+fn is_compressed_tag(tag: u8) -> bool{
+    tag == 0x02 || tag == 0x03
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_validated_constructors(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        assert_eq!(PrivateKey::new(BigUint::from(0u32), &signature_scheme).unwrap_err(), Error::ScalarOutOfRange);
+        assert_eq!(PrivateKey::new(BigUint::from(113u32), &signature_scheme).unwrap_err(), Error::ScalarOutOfRange);
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+
+        let pub_key = priv_key.public_key(&signature_scheme);
+        assert_eq!(PublicKey::new(pub_key.point().clone(), &signature_scheme), Ok(pub_key.clone()));
+        assert_eq!(PublicKey::new(Point::Identity, &signature_scheme), Err(Error::IdentityPublicKey));
+        let off_curve = Point::Coor(BigUint::from(1u32), BigUint::from(1u32));
+        assert_eq!(PublicKey::new(off_curve.clone(), &signature_scheme), Err(Error::PointNotOnCurve));
+
+        let hash = BigUint::from(56u32);
+        let k_random = Nonce::new(BigUint::from(81u32), &signature_scheme).unwrap();
+        let l_random = Nonce::new(BigUint::from(63u32), &signature_scheme).unwrap();
+        let signature = signature_scheme.sign(&hash, &priv_key, &k_random, &l_random).unwrap();
+        let (r_point, s_point, t) = (signature.r_point().clone(), signature.s_point().clone(), signature.t().clone());
+
+        assert_eq!(Signature::new(r_point.clone(), s_point.clone(), t.clone(), &signature_scheme), Ok(signature.clone()));
+        assert_eq!(
+            Signature::new(Point::Identity, s_point.clone(), t.clone(), &signature_scheme),
+            Err(Error::IdentityInSignature)
+        );
+        assert_eq!(
+            Signature::new(r_point.clone(), off_curve, t, &signature_scheme),
+            Err(Error::PointNotOnCurve)
+        );
+        assert_eq!(
+            Signature::new(r_point, s_point, BigUint::from(113u32), &signature_scheme),
+            Err(Error::ScalarOutOfRange)
+        );
+
+        assert!(pub_key.verify(&signature_scheme, &hash, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_signature_bytes(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        let hash = BigUint::from(56u32);
+        let k_random = Nonce::new(BigUint::from(81u32), &signature_scheme).unwrap();
+        let l_random = Nonce::new(BigUint::from(63u32), &signature_scheme).unwrap();
+        let signature = signature_scheme.sign(&hash, &priv_key, &k_random, &l_random).unwrap();
+
+        // 3 + 3 + 1 bytes
+        assert_eq!(Signature::size(&signature_scheme), 7);
+        let bytes = signature.to_bytes(&signature_scheme);
+        assert_eq!(bytes.len(), 7);
+        assert_eq!(Signature::from_bytes(&bytes, &signature_scheme), Ok(signature.clone()));
+
+        assert_eq!(Signature::from_bytes(&bytes[..6], &signature_scheme), Err(Error::InvalidEncoding));
+        assert_eq!(Signature::from_bytes(&[&bytes[..], &[0]].concat(), &signature_scheme), Err(Error::InvalidEncoding));
+
+        // t = q
+        let mut big_t = bytes.clone();
+        big_t[6] = 113;
+        assert_eq!(Signature::from_bytes(&big_t, &signature_scheme), Err(Error::ScalarOutOfRange));
+
+        // the identity and the uncompressed tag are not accepted
+        let mut identity_r = bytes.clone();
+        identity_r[..3].copy_from_slice(&[0x00, 0x00, 0x00]);
+        assert_eq!(Signature::from_bytes(&identity_r, &signature_scheme), Err(Error::InvalidEncoding));
+        let mut uncompressed_s = bytes.clone();
+        uncompressed_s[3] = 0x04;
+        assert_eq!(Signature::from_bytes(&uncompressed_s, &signature_scheme), Err(Error::InvalidEncoding));
+
+        // x = 0x02f5 = p, not reduced
+        let mut big_x = bytes.clone();
+        big_x[1..3].copy_from_slice(&[0x02, 0xf5]);
+        assert_eq!(Signature::from_bytes(&big_x, &signature_scheme), Err(Error::FieldElementOutOfRange));
+    }
+
+    #[test]
+    fn test_signature_bytes_secp256k1(){
+        let signature_scheme = crate::curves::secp256k1().signature_scheme();
+
+        let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+        let signature = signature_scheme.sign_message(b"hello world", &priv_key).unwrap();
+
+        // 33 + 33 + 32 bytes
+        let bytes = signature.to_bytes(&signature_scheme);
+        assert_eq!(bytes.len(), 98);
+        let decoded = Signature::from_bytes(&bytes, &signature_scheme).unwrap();
+        assert!(signature_scheme.verify_message(b"hello world", &pub_key, &decoded).unwrap());
+
+        // flipping the parity of R gives -R, a well-formed but wrong signature
+        let mut negated_r = bytes.clone();
+        negated_r[0] ^= 0x01;
+        let decoded = Signature::from_bytes(&negated_r, &signature_scheme).unwrap();
+        assert!(!signature_scheme.verify_message(b"hello world", &pub_key, &decoded).unwrap());
+    }
+
+    #[test]
+    fn test_signature_der(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        let k_random = Nonce::new(BigUint::from(81u32), &signature_scheme).unwrap();
+        let l_random = Nonce::new(BigUint::from(63u32), &signature_scheme).unwrap();
+        let signature = signature_scheme.sign(&BigUint::from(56u32), &priv_key, &k_random, &l_random).unwrap();
+
+        let encoded = signature.to_der();
+        assert_eq!(encoded[0], 0x30);
+        assert_eq!(encoded[1] as usize, encoded.len() - 2);
+        assert_eq!(Signature::from_der(&encoded, &signature_scheme), Ok(signature.clone()));
+
+        let Signature { r_point, s_point, t } = signature;
+        let (Point::Coor(rx, ry), Point::Coor(sx, sy)) = (&r_point, &s_point) else {
+            panic!("signature points are never the identity");
+        };
+        let build = |parts: &[Vec<u8>]| der::tlv(der::TAG_SEQUENCE, &parts.concat());
+        let point = |x: &BigUint, y: &BigUint| build(&[der::integer(x), der::integer(y)]);
+
+        let rebuilt = build(&[point(rx, ry), point(sx, sy), der::integer(&t)]);
+        assert_eq!(rebuilt, encoded);
+
+        // trailing data, inside the sequence and after it
+        let extra = build(&[point(rx, ry), point(sx, sy), der::integer(&t), der::integer(&t)]);
+        assert_eq!(Signature::from_der(&extra, &signature_scheme), Err(Error::InvalidEncoding));
+        assert_eq!(Signature::from_der(&[&encoded[..], &[0]].concat(), &signature_scheme), Err(Error::InvalidEncoding));
+
+        // BER long form length for a short sequence
+        let mut long_form = vec![0x30, 0x81];
+        long_form.extend_from_slice(&encoded[1..]);
+        assert_eq!(Signature::from_der(&long_form, &signature_scheme), Err(Error::InvalidEncoding));
+
+        // missing t
+        let short = build(&[point(rx, ry), point(sx, sy)]);
+        assert_eq!(Signature::from_der(&short, &signature_scheme), Err(Error::InvalidEncoding));
+
+        // well-formed DER that is not a valid signature
+        let big_t = build(&[point(rx, ry), point(sx, sy), der::integer(&BigUint::from(113u32))]);
+        assert_eq!(Signature::from_der(&big_t, &signature_scheme), Err(Error::ScalarOutOfRange));
+        let off_curve = build(&[point(rx, ry), point(sx, &(sy + 1u32)), der::integer(&t)]);
+        assert_eq!(Signature::from_der(&off_curve, &signature_scheme), Err(Error::PointNotOnCurve));
+        // (x + p, y) satisfies the curve equation, it must not be a second encoding of the point
+        let p = &signature_scheme.curve().p;
+        let unreduced_rx = build(&[point(&(rx + p), ry), point(sx, sy), der::integer(&t)]);
+        assert_eq!(Signature::from_der(&unreduced_rx, &signature_scheme), Err(Error::FieldElementOutOfRange));
+        let unreduced_sy = build(&[point(rx, ry), point(sx, &(sy + p)), der::integer(&t)]);
+        assert_eq!(Signature::from_der(&unreduced_sy, &signature_scheme), Err(Error::FieldElementOutOfRange));
+        let identity = build(&[der::tlv(der::TAG_SEQUENCE, &[]), point(sx, sy), der::integer(&t)]);
+        assert_eq!(Signature::from_der(&identity, &signature_scheme), Err(Error::InvalidEncoding));
+    }
+
+    #[test]
+    fn test_private_key_is_not_printed(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let mut priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        assert_eq!(format!("{:?}", priv_key), "PrivateKey(<redacted>)");
+        assert!(!format!("{:?}", priv_key).contains("78"));
+
+        priv_key.zeroize();
+        assert_eq!(priv_key.scalar(), &BigUint::from(0u32));
+
+        let mut nonce = Nonce::new(BigUint::from(81u32), &signature_scheme).unwrap();
+        assert_eq!(format!("{:?}", nonce), "Nonce(<redacted>)");
+        nonce.zeroize();
+        assert_eq!(nonce.scalar, BigUint::from(0u32));
+    }
+}
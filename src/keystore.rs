@@ -0,0 +1,255 @@
+use crate::keys::PrivateKey;
+use crate::signature::ElGamallikeSignature;
+use crate::Error;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use std::io::Write;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+// PASSWORD-ENCRYPTED KEYSTORE
+// the PKCS#8 DER of the key (see key_encoding.rs, it carries the curve parameters) encrypted
+// with ChaCha20-Poly1305 under a key derived from the password with scrypt:
+// magic "EGLK" || version 1 || log2(N) || r (4 bytes) || p (4 bytes) || salt (16 bytes)
+// || nonce (12 bytes) || ciphertext with the 16-byte tag
+// everything before the ciphertext is the associated data, so the scrypt cost and the salt
+// cannot be changed without failing the tag check like a wrong password does
+const MAGIC: &[u8; 4] = b"EGLK";
+const VERSION: u8 = 1;
+
+// N = 2^15, r = 8, p = 1, the scrypt paper's interactive login cost (32 MiB)
+const DEFAULT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+// keystores that ask for more than 1 GiB (128 * r * N bytes) or p > 16 are refused
+const MAX_MEMORY: u64 = 1 << 30;
+const MAX_P: u32 = 16;
+
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const HEADER_SIZE: usize = MAGIC.len() + 1 + 1 + 4 + 4 + SALT_SIZE + NONCE_SIZE;
+
+impl PrivateKey {
+    pub fn to_keystore<D>(&self, password: &[u8], signature_scheme: &ElGamallikeSignature<D>) -> Vec<u8>{
+        self.to_keystore_with_cost(password, DEFAULT_LOG_N, signature_scheme)
+    }
+
+    fn to_keystore_with_cost<D>(&self, password: &[u8], log_n: u8, signature_scheme: &ElGamallikeSignature<D>) -> Vec<u8>{
+        let mut rng = rand::thread_rng();
+        let mut salt = [0u8; SALT_SIZE];
+        let mut nonce = [0u8; NONCE_SIZE];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut nonce);
+
+        let mut out = Vec::with_capacity(HEADER_SIZE);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(log_n);
+        out.extend_from_slice(&SCRYPT_R.to_be_bytes());
+        out.extend_from_slice(&SCRYPT_P.to_be_bytes());
+        out.extend_from_slice(&salt);
+        out.extend_from_slice(&nonce);
+
+        let key = derive_key(password, log_n, SCRYPT_R, SCRYPT_P, &salt).expect("the scrypt cost is valid");
+        let plaintext = self.to_pkcs8_der(signature_scheme);
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &out })
+            .expect("a key is far below the ChaCha20-Poly1305 message limit");
+        out.extend(ciphertext);
+        out
+    }
+
+    // Error::WrongPassword when the tag does not verify, which is also what a tampered
+    // keystore gives
+    pub fn from_keystore<D>(bytes: &[u8], password: &[u8], signature_scheme: &ElGamallikeSignature<D>) -> Result<Self, Error>{
+        if bytes.len() < HEADER_SIZE || &bytes[..MAGIC.len()] != MAGIC || bytes[MAGIC.len()] != VERSION {
+            return Err(Error::InvalidEncoding);
+        }
+        let (header, ciphertext) = bytes.split_at(HEADER_SIZE);
+        let log_n = header[5];
+        let r = u32::from_be_bytes(header[6..10].try_into().expect("4 bytes"));
+        let p = u32::from_be_bytes(header[10..14].try_into().expect("4 bytes"));
+        let salt = &header[14..14 + SALT_SIZE];
+        let nonce = &header[14 + SALT_SIZE..];
+
+        // 128 * r * 2^log_n bytes of memory, 128 * r fits in a u64 for any 32-bit r
+        let memory = 1u64.checked_shl(log_n as u32).and_then(|n| n.checked_mul(128 * r as u64));
+        if memory.is_none_or(|memory| memory > MAX_MEMORY) || p > MAX_P {
+            return Err(Error::InvalidEncoding);
+        }
+        let key = derive_key(password, log_n, r, p, salt)?;
+        let plaintext = ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+            .map(Zeroizing::new)
+            .map_err(|_| Error::WrongPassword)?;
+        PrivateKey::from_pkcs8_der(&plaintext, signature_scheme)
+    }
+
+    // the keystore goes to a new temporary file next to path (readable by its owner only on
+    // unix), which is synced and then renamed over path: a crash leaves either the old file or
+    // the new one, and a symlink at path is replaced instead of followed
+    pub fn save<D, P: AsRef<Path>>(&self, path: P, password: &[u8], signature_scheme: &ElGamallikeSignature<D>) -> Result<(), Error>{
+        self.save_with_cost(path, password, DEFAULT_LOG_N, signature_scheme)
+    }
+
+    fn save_with_cost<D, P: AsRef<Path>>(&self, path: P, password: &[u8], log_n: u8, signature_scheme: &ElGamallikeSignature<D>) -> Result<(), Error>{
+        let path = path.as_ref();
+        let keystore = self.to_keystore_with_cost(password, log_n, signature_scheme);
+        let file_name = path.file_name().ok_or(Error::Io(std::io::ErrorKind::InvalidInput))?;
+        let mut temp_name = std::ffi::OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(format!(".{:016x}.tmp", rand::random::<u64>()));
+        let temp_path = path.with_file_name(temp_name);
+
+        let result = write_new_file(&temp_path, &keystore).and_then(|()| std::fs::rename(&temp_path, path));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        Ok(result?)
+    }
+
+    pub fn load<D, P: AsRef<Path>>(path: P, password: &[u8], signature_scheme: &ElGamallikeSignature<D>) -> Result<Self, Error>{
+        Self::from_keystore(&std::fs::read(path)?, password, signature_scheme)
+    }
+}
+
+// create_new fails instead of opening an existing file or following a symlink
+fn write_new_file(path: &Path, contents: &[u8]) -> std::io::Result<()>{
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+fn derive_key(password: &[u8], log_n: u8, r: u32, p: u32, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, Error>{
+    let params = scrypt::Params::new(log_n, r, p, 32).map_err(|_| Error::InvalidEncoding)?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt::scrypt(password, salt, &params, key.as_mut_slice()).map_err(|_| Error::InvalidEncoding)?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+    use num_bigint::BigUint;
+
+    // a cheap scrypt cost, the default one is slow in debug builds
+    const TEST_LOG_N: u8 = 4;
+
+    #[test]
+    fn test_keystore(){
+        let toy757 = crate::curves::toy757();
+        let signature_scheme = toy757.signature_scheme();
+        let (ec, gen) = (toy757.curve, toy757.generator);
+
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        let keystore = priv_key.to_keystore_with_cost(b"correct horse", TEST_LOG_N, &signature_scheme);
+
+        assert_eq!(PrivateKey::from_keystore(&keystore, b"correct horse", &signature_scheme).unwrap().scalar(), priv_key.scalar());
+        assert_eq!(PrivateKey::from_keystore(&keystore, b"battery staple", &signature_scheme).unwrap_err(), Error::WrongPassword);
+
+        // salt and nonce are fresh every time
+        let again = priv_key.to_keystore_with_cost(b"correct horse", TEST_LOG_N, &signature_scheme);
+        assert_ne!(again, keystore);
+
+        // the header is authenticated
+        let mut tampered = keystore.clone();
+        tampered[14] ^= 0x01;
+        assert_eq!(PrivateKey::from_keystore(&tampered, b"correct horse", &signature_scheme).unwrap_err(), Error::WrongPassword);
+        let mut tampered = keystore.clone();
+        *tampered.last_mut().unwrap() ^= 0x01;
+        assert_eq!(PrivateKey::from_keystore(&tampered, b"correct horse", &signature_scheme).unwrap_err(), Error::WrongPassword);
+
+        assert_eq!(PrivateKey::from_keystore(&keystore[..HEADER_SIZE - 1], b"correct horse", &signature_scheme).unwrap_err(), Error::InvalidEncoding);
+        let mut other_version = keystore.clone();
+        other_version[4] = 2;
+        assert_eq!(PrivateKey::from_keystore(&other_version, b"correct horse", &signature_scheme).unwrap_err(), Error::InvalidEncoding);
+        // N = 2^40 is refused before any work is done
+        let mut expensive = keystore.clone();
+        expensive[5] = 40;
+        assert_eq!(PrivateKey::from_keystore(&expensive, b"correct horse", &signature_scheme).unwrap_err(), Error::InvalidEncoding);
+        // r = 3 and N = 2^22 is 1.5 GiB, rounding log2(r) down used to let it through
+        let mut expensive = keystore.clone();
+        expensive[5] = 22;
+        expensive[6..10].copy_from_slice(&3u32.to_be_bytes());
+        assert_eq!(PrivateKey::from_keystore(&expensive, b"correct horse", &signature_scheme).unwrap_err(), Error::InvalidEncoding);
+        expensive[5] = 255;
+        assert_eq!(PrivateKey::from_keystore(&expensive, b"correct horse", &signature_scheme).unwrap_err(), Error::InvalidEncoding);
+
+        // the key is bound to its curve parameters
+        let other_scheme = ElGamallikeSignature::new(ec, gen, BigUint::from(109u32));
+        assert_eq!(PrivateKey::from_keystore(&keystore, b"correct horse", &other_scheme).unwrap_err(), Error::CurveMismatch);
+    }
+
+    #[test]
+    fn test_keystore_save_and_load(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+        let path = std::env::temp_dir().join(format!("elgamal-like-keystore-{}.key", std::process::id()));
+
+        priv_key.save_with_cost(&path, b"correct horse", TEST_LOG_N, &signature_scheme).unwrap();
+        let loaded = PrivateKey::load(&path, b"correct horse", &signature_scheme);
+        let wrong = PrivateKey::load(&path, b"battery staple", &signature_scheme);
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::metadata(&path).unwrap().permissions().mode() & 0o777
+        };
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap().public_key(&signature_scheme), pub_key);
+        assert_eq!(wrong.unwrap_err(), Error::WrongPassword);
+        #[cfg(unix)]
+        assert_eq!(mode, 0o600);
+
+        let missing = PrivateKey::load(&path, b"correct horse", &signature_scheme);
+        assert_eq!(missing.unwrap_err(), Error::Io(std::io::ErrorKind::NotFound));
+
+        // overwriting a world-readable file restricts it as well
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::write(&path, b"old").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+            priv_key.save_with_cost(&path, b"correct horse", TEST_LOG_N, &signature_scheme).unwrap();
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+            let loaded = PrivateKey::load(&path, b"correct horse", &signature_scheme);
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(mode, 0o600);
+            assert_eq!(loaded.unwrap().scalar(), priv_key.scalar());
+        }
+
+        // a symlink at path is replaced, its target is left alone
+        #[cfg(unix)]
+        {
+            let target = path.with_extension("target");
+            std::fs::write(&target, b"old").unwrap();
+            std::os::unix::fs::symlink(&target, &path).unwrap();
+            priv_key.save_with_cost(&path, b"correct horse", TEST_LOG_N, &signature_scheme).unwrap();
+            let is_symlink = std::fs::symlink_metadata(&path).unwrap().file_type().is_symlink();
+            let target_contents = std::fs::read(&target).unwrap();
+            let loaded = PrivateKey::load(&path, b"correct horse", &signature_scheme);
+            std::fs::remove_file(&path).unwrap();
+            std::fs::remove_file(&target).unwrap();
+            assert!(!is_symlink);
+            assert_eq!(target_contents, b"old");
+            assert_eq!(loaded.unwrap().scalar(), priv_key.scalar());
+        }
+
+        // no temporary file is left behind
+        let temp_files = std::fs::read_dir(std::env::temp_dir()).unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&format!(".{}.", path.file_name().unwrap().to_string_lossy())))
+            .count();
+        assert_eq!(temp_files, 0);
+    }
+}
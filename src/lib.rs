@@ -1,4 +1,27 @@
-use num_bigint::{BigUint, BigInt};
+#![allow(non_snake_case)]
+
+use num_bigint::BigUint;
+use primality::is_probable_prime;
+
+pub mod curves;
+pub mod der;
+pub mod error;
+mod fixed_base;
+mod jacobian;
+mod key_encoding;
+pub mod keys;
+mod keystore;
+mod msm;
+mod nonce;
+mod pem;
+mod primality;
+mod sec1;
+mod secret;
+pub mod signature;
+mod wnaf;
+
+pub use error::{Error, ParameterError};
+pub use keys::{Nonce, PrivateKey, PublicKey, Signature};
 
 /* struct Point{
     //we cannot use Point because sometimes we require identity
@@ -11,6 +34,7 @@ pub enum Point{
     Coor(BigUint,BigUint),
     Identity,
 }
+#[derive(Clone, Debug, PartialEq)]
 pub struct EllipticCurve{
     // y^2 = x^2 + ax + b
     pub a: BigUint,
@@ -19,44 +43,87 @@ pub struct EllipticCurve{
 }
 
 impl EllipticCurve {
+    // the curve y^2 = x^3 + ax + b mod p after the checks of validate
+    pub fn try_new(a: BigUint, b: BigUint, p: BigUint) -> Result<Self, Error>{
+        let ec = EllipticCurve { a, b, p };
+        ec.validate()?;
+        Ok(ec)
+    }
+
+    // p is a prime greater than 3 (Miller-Rabin), a and b are reduced mod p and
+    // 4a^3 + 27b^2 != 0 mod p, i.e. the curve has no singular point
+    pub fn validate(&self) -> Result<(), Error>{
+        if self.p <= BigUint::from(3u32) || !is_probable_prime(&self.p) {
+            return Err(ParameterError::ModulusNotPrime.into());
+        }
+        if self.a >= self.p || self.b >= self.p {
+            return Err(ParameterError::CoefficientOutOfRange.into());
+        }
+        let a3 = self.a.modpow(&BigUint::from(3u32), &self.p);
+        let b2 = self.b.modpow(&BigUint::from(2u32), &self.p);
+        let discriminant = FiniteField::add(
+            &FiniteField::mul(&BigUint::from(4u32), &a3, &self.p),
+            &FiniteField::mul(&BigUint::from(27u32), &b2, &self.p),
+            &self.p,
+        );
+        if discriminant == BigUint::from(0u32) {
+            return Err(ParameterError::SingularCurve.into());
+        }
+        Ok(())
+    }
+
     pub fn add(&self, c: &Point, d: &Point) -> Point{
-        assert!(self.is_on_curve(c),"First point is not in curve");
-        assert!(self.is_on_curve(d), "Second point is not in curve");
-        assert!(c != d, "Points need to be different");
+        self.try_add(c, d).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_add(&self, c: &Point, d: &Point) -> Result<Point, Error>{
+        if !self.is_on_curve(c) || !self.is_on_curve(d) {
+            return Err(Error::PointNotOnCurve);
+        }
+        // the chord formula needs two different points, P + P is the tangent (doubling) case
+        if c == d {
+            return self.try_double(c);
+        }
 
         match (c,d) {
-            (Point::Identity,d) => d.clone(),
-            (c, Point::Identity) => c.clone(),
+            (Point::Identity,d) => Ok(d.clone()),
+            (c, Point::Identity) => Ok(c.clone()),
             (Point::Coor(x1, y1), Point::Coor(x2,y2 )) => {
-                let y1_plus_y2 = FiniteField::add(&y1, &y2, &self.p);
+                let y1_plus_y2 = FiniteField::add(y1, y2, &self.p);
                 if x1 == x2 && y1_plus_y2 == BigUint::from(0u32){
-                    return Point::Identity;
+                    return Ok(Point::Identity);
                 }
                 // s = (y2 - y1) / (x2 - x1) mod p (slope of a line )
                 // x3 = s^2 - x1 - x2 mod p 
                 // y3 = s(x1 - x3) - y1 mod p 
                 let y2_minus_y1 = FiniteField::subs(y2, y1, &self.p);
                 let x2_minus_x1 = FiniteField::subs(x2, x1, &self.p);
-                let s = FiniteField::div(&y2_minus_y1, &x2_minus_x1, &self.p);
+                let s = FiniteField::try_div(&y2_minus_y1, &x2_minus_x1, &self.p)?;
                 let x1_plus_x2 = FiniteField::add(x1, x2, &self.p);
                 let s_square = FiniteField::mul(&s, &s, &self.p);
                 let x3 = FiniteField::subs(&s_square, &x1_plus_x2, &self.p);
                 let x1_minus_x3 = FiniteField::subs(x1, &x3, &self.p);
                 let mut y3 = FiniteField::mul(&s, &x1_minus_x3, &self.p);
                 y3 = FiniteField::subs(&y3,y1, &self.p);
-                Point::Coor(x3, y3)
+                Ok(Point::Coor(x3, y3))
             } 
         }
       
     }
 
     pub fn double(&self, c: &Point) -> Point{
-        assert!(self.is_on_curve(c),"First point is not in curve");
+        self.try_double(c).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_double(&self, c: &Point) -> Result<Point, Error>{
+        if !self.is_on_curve(c) {
+            return Err(Error::PointNotOnCurve);
+        }
         match c {
-            Point::Identity => Point::Identity,
+            Point::Identity => Ok(Point::Identity),
             Point::Coor(x1, y1) => {
                 if y1 == &BigUint::from(0u32) {
-                    return Point::Identity;
+                    return Ok(Point::Identity);
                 }
                 //y^2 = x^2 + ax + b (derivative of the equation)
                 // s = (3x1^2 + a) / (2 * y1) mod p 
@@ -66,33 +133,60 @@ impl EllipticCurve {
                 let x1_square_3x = FiniteField::mul(&x1_square, &BigUint::from(3u32), &self.p);
                 let x1_square_3x_plus_a = FiniteField::add(&x1_square_3x, &self.a, &self.p);
                 let y1_2x = FiniteField::mul(y1, &BigUint::from(2u32),&self.p);
-                let s = FiniteField::div(&x1_square_3x_plus_a, &y1_2x, &self.p);
+                let s = FiniteField::try_div(&x1_square_3x_plus_a, &y1_2x, &self.p)?;
                 let x1_mul_2 = FiniteField::mul(x1, &BigUint::from(2u32), &self.p);
                 let s_square = FiniteField::mul(&s, &s, &self.p);
                 let x3 = FiniteField::subs(&s_square, &x1_mul_2, &self.p);
                 let x1_minus_x3 = FiniteField::subs(x1, &x3, &self.p);
                 let mut y3 = FiniteField::mul(&s, &x1_minus_x3, &self.p);
                 y3 = FiniteField::subs(&y3,y1, &self.p);
-                Point::Coor(x3, y3)
+                Ok(Point::Coor(x3, y3))
             } 
         }
 
     }
 
+    pub fn negate(&self, c: &Point) -> Point{
+        // -(x, y) = (x, -y)
+        match c {
+            Point::Identity => Point::Identity,
+            Point::Coor(x, y) => Point::Coor(x.clone(), (&self.p - y) % &self.p),
+        }
+    }
+
     pub fn scalar_mul(&self, c: &Point, exponent: &BigUint) -> Point{
-        let mut t = c.clone(); 
-        for i in (0..exponent.bits()-1).rev(){
-            t = self.double(&t);
-            if exponent.bit(i){
-                t = self.add(&t, c);
-            } 
+        self.try_scalar_mul(c, exponent).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_scalar_mul(&self, c: &Point, exponent: &BigUint) -> Result<Point, Error>{
+        if !self.is_on_curve(c) {
+            return Err(Error::PointNotOnCurve);
         }
-        t
+        let t = self.scalar_mul_jacobian(&self.to_jacobian(c), exponent);
+        Ok(self.to_affine(&t))
     }
 
+    // scalar multiplication for secret scalars (private keys and nonces)
+    // uses the Montgomery ladder, see jacobian.rs
+    // scalar_mul is variable time and must only be used with public scalars
+    pub fn scalar_mul_ct(&self, c: &Point, exponent: &BigUint) -> Point{
+        self.try_scalar_mul_ct(c, exponent).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_scalar_mul_ct(&self, c: &Point, exponent: &BigUint) -> Result<Point, Error>{
+        if !self.is_on_curve(c) {
+            return Err(Error::PointNotOnCurve);
+        }
+        let t = self.scalar_mul_ladder(&self.to_jacobian(c), exponent);
+        Ok(self.to_affine(&t))
+    }
+
+    // the coordinates have to be reduced mod p, (x + p, y) is not a point of the curve even
+    // though it satisfies the equation: the field arithmetic expects reduced inputs
     pub fn is_on_curve(&self, c: &Point) -> bool {
         // y^2 = x^3 + ax + b 
         match c {
+            Point::Coor(x, y) if *x >= self.p || *y >= self.p => false,
             Point::Coor(x,y ) => {
                 let y2 = y.modpow(&BigUint::from(2u32), &self.p); 
                 let x3 = x.modpow(&BigUint::from(3u32), &self.p);
@@ -116,39 +210,189 @@ impl FiniteField {
 
     pub fn add(c: &BigUint, d: &BigUint, p:&BigUint) -> BigUint{
         let r = c + d; 
-        r.modpow(&BigUint::from(1u32),p)
+        r % p
     }
 
     pub fn subs(c: &BigUint, d: &BigUint, p:&BigUint) -> BigUint{
-        let d_inv = FiniteField::inv_add(d, p);
-        FiniteField::add(c, &d_inv, p)
+        FiniteField::try_subs(c, d, p).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_subs(c: &BigUint, d: &BigUint, p:&BigUint) -> Result<BigUint, Error>{
+        let d_inv = FiniteField::try_inv_add(d, p)?;
+        Ok(FiniteField::add(c, &d_inv, p))
     }
 
     pub fn mul(c: &BigUint, d: &BigUint, p:&BigUint) -> BigUint{
         let r = c * d;
-        r.modpow(&BigUint::from(1u32),p)
+        r % p
     }
 
     pub fn div(c: &BigUint, d: &BigUint, p:&BigUint) -> BigUint{
-        let d_inv = FiniteField::inv_mul(d, p);
-        FiniteField::mul(c, &d_inv, p)
+        FiniteField::try_div(c, d, p).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_div(c: &BigUint, d: &BigUint, p:&BigUint) -> Result<BigUint, Error>{
+        let d_inv = FiniteField::try_inv_mul(d, p)?;
+        Ok(FiniteField::mul(c, &d_inv, p))
     }
 
     pub fn inv_add(c: &BigUint, p:&BigUint) -> BigUint{
-        assert!(c < p);
-        p - c 
+        FiniteField::try_inv_add(c, p).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_inv_add(c: &BigUint, p:&BigUint) -> Result<BigUint, Error>{
+        if c >= p {
+            return Err(Error::FieldElementOutOfRange);
+        }
+        // -0 is 0, not p
+        Ok((p - c) % p)
     }
 
     pub fn inv_mul(c: &BigUint, p:&BigUint) -> BigUint{
+        FiniteField::try_inv_mul(c, p).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_inv_mul(c: &BigUint, p:&BigUint) -> Result<BigUint, Error>{
+        if (c % p) == BigUint::from(0u32) {
+            return Err(Error::NonInvertible);
+        }
         // it works only p is prime 
-        c.modpow(&(p-BigUint::from(2u32)), p)
+        Ok(c.modpow(&(p-BigUint::from(2u32)), p))
+    }
+
+    // Legendre symbol (c / p) for an odd prime p: 0 if c = 0 mod p, 1 if c is a square
+    // mod p and -1 otherwise
+    // computed with the quadratic reciprocity steps of the Jacobi symbol, so no modpow
+    // None when p is below 3 or even, for an odd composite p the result is the Jacobi symbol,
+    // which does not tell whether c is a square
+    pub fn legendre_symbol(c: &BigUint, p: &BigUint) -> Option<i8>{
+        if *p < BigUint::from(3u32) || !p.bit(0) {
+            return None;
+        }
+        let zero = BigUint::from(0u32);
+        let mut a = c % p;
+        let mut n = p.clone();
+        let mut result = 1i8;
+        while a != zero {
+            // (2 / n) = -1 when n = 3 or 5 mod 8
+            let twos = a.trailing_zeros().unwrap_or(0);
+            a >>= twos;
+            let n_mod_8 = n.iter_u32_digits().next().unwrap_or(0) & 7;
+            if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+                result = -result;
+            }
+            // (a / n) = -(n / a) when both are 3 mod 4
+            std::mem::swap(&mut a, &mut n);
+            if a.bit(1) && n.bit(1) {
+                result = -result;
+            }
+            a %= &n;
+        }
+        if n == BigUint::from(1u32) {
+            Some(result)
+        } else {
+            Some(0)
+        }
+    }
+
+    // a square root of c mod p for an odd prime p, None if c is not a square
+    // the other root is p - root
+    // p may come from an untrusted curve (decode_point), so for an even or composite p this
+    // gives up with None instead of looping: the search for a non-square is bounded, every
+    // Tonelli-Shanks step is capped, and the result is checked
+    pub fn sqrt(c: &BigUint, p: &BigUint) -> Option<BigUint>{
+        let one = BigUint::from(1u32);
+        if *p < BigUint::from(3u32) || !p.bit(0) {
+            return None;
+        }
+        let c = c % p;
+        if c == BigUint::from(0u32) {
+            return Some(c);
+        }
+        if FiniteField::legendre_symbol(&c, p) != Some(1) {
+            return None;
+        }
+
+        // p = 3 mod 4 (secp256k1, P-256, ...): c^((p + 1) / 4)
+        if p.bit(1) {
+            let root = c.modpow(&((p + &one) >> 2), p);
+            return (FiniteField::mul(&root, &root, p) == c).then_some(root);
+        }
+
+        // Tonelli-Shanks, https://en.wikipedia.org/wiki/Tonelli%E2%80%93Shanks_algorithm
+        // p - 1 = q * 2^s with q odd
+        let p_minus_one = p - &one;
+        let s = p_minus_one.trailing_zeros().unwrap_or(0);
+        let q = &p_minus_one >> s;
+
+        // any non-square z, half of the elements are so this ends quickly
+        // for a prime p the least one is below 2 ln(p)^2 < 2 bits^2 (under GRH)
+        let bound = std::cmp::min(BigUint::from(2 * p.bits() * p.bits() + 2), p.clone());
+        let mut z = BigUint::from(2u32);
+        while FiniteField::legendre_symbol(&z, p) != Some(-1) {
+            z += &one;
+            if z >= bound {
+                return None;
+            }
+        }
+
+        let mut m = s;
+        let mut b = z.modpow(&q, p);
+        let mut t = c.modpow(&q, p);
+        let mut root = c.modpow(&((&q + &one) >> 1), p);
+        while t != one {
+            // the least i with t^(2^i) = 1, it is below m since c is a square
+            let mut i = 0;
+            let mut t_pow = t.clone();
+            while t_pow != one {
+                t_pow = FiniteField::mul(&t_pow, &t_pow, p);
+                i += 1;
+                if i >= m {
+                    return None;
+                }
+            }
+            let mut e = b.clone();
+            for _ in 0..(m - i - 1) {
+                e = FiniteField::mul(&e, &e, p);
+            }
+            m = i;
+            b = FiniteField::mul(&e, &e, p);
+            t = FiniteField::mul(&t, &b, p);
+            root = FiniteField::mul(&root, &e, p);
+        }
+        (FiniteField::mul(&root, &root, p) == c).then_some(root)
     }
 }
 
 
+#[cfg(test)]
 mod test{
     use super::*;
 
+    #[test]
+    fn test_curve_try_new(){
+        // https://arxiv.org/ftp/arxiv/papers/1301/1301.2335.pdf example 5.1.
+        let p = BigUint::from(757u32);
+        let ec = EllipticCurve::try_new(BigUint::from(6u32), BigUint::from(2u32), p.clone()).unwrap();
+        assert_eq!(ec, EllipticCurve{ a: BigUint::from(6u32), b: BigUint::from(2u32), p: p.clone() });
+
+        let invalid = |a: u32, b: u32, p: u32| EllipticCurve::try_new(BigUint::from(a), BigUint::from(b), BigUint::from(p));
+        assert_eq!(invalid(6, 2, 758), Err(Error::InvalidParameters(ParameterError::ModulusNotPrime)));
+        assert_eq!(invalid(6, 2, 761 * 757), Err(Error::InvalidParameters(ParameterError::ModulusNotPrime)));
+        // 2 and 3 are prime but 4a^3 + 27b^2 says nothing about singularity there
+        assert_eq!(invalid(1, 1, 3), Err(Error::InvalidParameters(ParameterError::ModulusNotPrime)));
+        assert_eq!(invalid(757, 2, 757), Err(Error::InvalidParameters(ParameterError::CoefficientOutOfRange)));
+        assert_eq!(invalid(6, 760, 757), Err(Error::InvalidParameters(ParameterError::CoefficientOutOfRange)));
+        // y^2 = x^3 and y^2 = x^3 - 3x + 2 = (x - 1)^2 (x + 2)
+        assert_eq!(invalid(0, 0, 757), Err(Error::InvalidParameters(ParameterError::SingularCurve)));
+        assert_eq!(invalid(754, 2, 757), Err(Error::InvalidParameters(ParameterError::SingularCurve)));
+
+        assert_eq!(
+            invalid(0, 0, 757).unwrap_err().to_string(),
+            "invalid domain parameters: the curve is singular, 4a^3 + 27b^2 = 0 mod p"
+        );
+    }
+
     #[test]
     fn test_add(){
         let c: BigUint = BigUint::from(4u32);
@@ -193,6 +437,68 @@ mod test{
     }
 
 
+    #[test]
+    fn test_legendre_symbol(){
+        // Euler's criterion: (c / p) = c^((p - 1) / 2) mod p
+        for p in [17u32, 113, 757] {
+            let p = BigUint::from(p);
+            let half = (&p - BigUint::from(1u32)) >> 1;
+            for c in 0u32..200 {
+                let c = BigUint::from(c);
+                let euler = c.modpow(&half, &p);
+                let expected = if euler == BigUint::from(0u32) {
+                    0
+                } else if euler == BigUint::from(1u32) {
+                    1
+                } else {
+                    -1
+                };
+                assert_eq!(FiniteField::legendre_symbol(&c, &p), Some(expected));
+            }
+        }
+
+        for p in [0u32, 1, 2, 4, 758] {
+            assert_eq!(FiniteField::legendre_symbol(&BigUint::from(3u32), &BigUint::from(p)), None);
+        }
+    }
+
+    #[test]
+    fn test_sqrt(){
+        // 17 - 1 = 2^4 and 757 - 1 = 189 * 2^2 need Tonelli-Shanks, 11 = 3 mod 4 does not
+        for p in [11u32, 17, 757] {
+            let p = BigUint::from(p);
+            let mut squares = 0;
+            for c in 0u32..p.to_u32_digits()[0] {
+                let c = BigUint::from(c);
+                match FiniteField::sqrt(&c, &p) {
+                    Some(root) => {
+                        assert_eq!(FiniteField::mul(&root, &root, &p), c);
+                        squares += 1;
+                    }
+                    None => assert_eq!(FiniteField::legendre_symbol(&c, &p), Some(-1)),
+                }
+            }
+            // 0 and the (p - 1) / 2 non-zero squares
+            assert_eq!(BigUint::from(2 * (squares - 1) + 1u32), p);
+        }
+
+        // the y of the generator of the paper curve y^2 = x^3 + 6x + 2 mod 757
+        let p = BigUint::from(757u32);
+        let rhs = BigUint::from((529u32 * 529 % 757 * 529 + 6 * 529 + 2) % 757);
+        let root = FiniteField::sqrt(&rhs, &p).unwrap();
+        assert!(root == BigUint::from(566u32) || root == BigUint::from(757u32 - 566));
+
+        // moduli that are not odd primes give up instead of looping forever
+        for p in [0u32, 1, 2, 4, 8, 9, 15, 25, 49, 65, 561, 1 << 20] {
+            let p = BigUint::from(p);
+            for c in 0u32..30 {
+                if let Some(root) = FiniteField::sqrt(&BigUint::from(c), &p) {
+                    assert_eq!(FiniteField::mul(&root, &root, &p), BigUint::from(c) % &p);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_point_add1(){
         //y^2 = x^3 + 2x + 2 mod 17
@@ -262,6 +568,43 @@ mod test{
    
     }
 
+    #[test]
+    fn test_point_add_equal_points(){
+        //y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        // (6,3) + (6,3) = 2*(6,3) = (3,1)
+        let p = Point::Coor(BigUint::from(6u32), BigUint::from(3u32));
+
+        let r = Point::Coor(BigUint::from(3u32), BigUint::from(1u32));
+
+        assert_eq!(ec.add(&p, &p), r);
+        assert_eq!(ec.add(&p, &p), ec.double(&p));
+        assert_eq!(ec.add(&Point::Identity, &Point::Identity), Point::Identity);
+    }
+
+    #[test]
+    fn test_point_add_negation(){
+        //y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        // -(6,3) = (6,14) and (6,3) + (6,14) = identity
+        let p = Point::Coor(BigUint::from(6u32), BigUint::from(3u32));
+        let minus_p = ec.negate(&p);
+
+        assert_eq!(minus_p, Point::Coor(BigUint::from(6u32), BigUint::from(14u32)));
+        assert_eq!(ec.add(&p, &minus_p), Point::Identity);
+        assert_eq!(ec.negate(&Point::Identity), Point::Identity);
+    }
+
     #[test]
     fn test_point_double_with_zero_y(){ 
         //y^2 = x^3 + 3x + 2 mod 23
@@ -367,51 +710,156 @@ mod test{
     }
 
     #[test]
-    fn test_secp256k1(){ 
-        //y^2 = x^3 + 7 mod 
-        // for any A, 19 * A == Identity because the order of curve is 19 
-
-        //modulo of the field
-        let p = BigUint::parse_bytes(
-            b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F", 
-            16
-        ).unwrap();
+    fn test_scalar_multiplication_zero(){ 
+        //y^2 = x^3 + 2x + 2 mod 17 
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
 
-        //order of the curve
-        let n = BigUint::parse_bytes(
-            b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141", 
-            16
-        ).unwrap();
+        // 0 * (5,1) = identity
+        let p = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
 
-        //generator points of the field 
-        let generator_x = BigUint::parse_bytes(
-            b"79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798", 
-            16
-        ).unwrap();
+        assert_eq!(ec.scalar_mul(&p, &BigUint::from(0u32)), Point::Identity);
+        assert_eq!(ec.scalar_mul_ct(&p, &BigUint::from(0u32)), Point::Identity);
+    }
 
-        let generator_y = BigUint::parse_bytes(
-            b"483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8", 
-            16
-        ).unwrap();
+    #[test]
+    fn test_scalar_multiplication_of_identity(){ 
+        //y^2 = x^3 + 2x + 2 mod 17 
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
 
+        // k * identity = identity
+        for k in [0u32, 1, 2, 19, 123] {
+            assert_eq!(ec.scalar_mul(&Point::Identity, &BigUint::from(k)), Point::Identity);
+            assert_eq!(ec.scalar_mul_ct(&Point::Identity, &BigUint::from(k)), Point::Identity);
+        }
+    }
 
-        //creating elliptic curve
+    #[test]
+    fn test_scalar_multiplication_reduces_mod_order(){ 
+        //y^2 = x^3 + 2x + 2 mod 17 
+        // for any A, 19 * A == Identity because the order of curve is 19 
         let ec = EllipticCurve{
-            a: BigUint::from(0u32),
-            b: BigUint::from(7u32),
-            p,
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
         };
 
-        let g = Point::Coor(generator_x, generator_y);
+        let p = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+
+        // multiples of the order
+        for m in [19u32, 38, 19 * 1000] {
+            assert_eq!(ec.scalar_mul(&p, &BigUint::from(m)), Point::Identity);
+            assert_eq!(ec.scalar_mul_ct(&p, &BigUint::from(m)), Point::Identity);
+        }
+
+        // k * A == (k mod 19) * A
+        for k in [20u32, 21, 37, 19 * 1000 + 5] {
+            let reduced = ec.scalar_mul(&p, &BigUint::from(k % 19));
+            assert_eq!(ec.scalar_mul(&p, &BigUint::from(k)), reduced);
+            assert_eq!(ec.scalar_mul_ct(&p, &BigUint::from(k)), reduced);
+        }
+    }
+
+    #[test]
+    fn test_secp256k1(){ 
+        //y^2 = x^3 + 7 mod 
+        // for any A, 19 * A == Identity because the order of curve is 19 
+
+        let secp256k1 = crate::curves::secp256k1();
+        let ec = secp256k1.curve;
+        let n = secp256k1.order;
+        let g = secp256k1.generator;
 
         let res = ec.scalar_mul(&g, &n);
 
         assert_eq!(res, Point::Identity);
 
+        let n_plus_one = &n + BigUint::from(1u32);
+        assert_eq!(ec.scalar_mul(&g, &n_plus_one), g);
+
         let g_double = ec.scalar_mul(&g, &BigUint::from(3u32));
 
         println!("{:2x?}", &g_double);
 
 
     }
+
+    #[test]
+    fn test_try_inv_add_out_of_range(){
+        let c: BigUint = BigUint::from(11u32);
+        let p: BigUint = BigUint::from(11u32);
+
+        assert_eq!(FiniteField::try_inv_add(&c, &p), Err(Error::FieldElementOutOfRange));
+    }
+
+    #[test]
+    fn test_try_inv_mul_zero(){
+        let c: BigUint = BigUint::from(0u32);
+        let p: BigUint = BigUint::from(11u32);
+
+        assert_eq!(FiniteField::try_inv_mul(&c, &p), Err(Error::NonInvertible));
+    }
+
+    #[test]
+    fn test_try_add_point_not_on_curve(){
+        //y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        // (6,4) is not on the curve
+        let p = Point::Coor(BigUint::from(6u32), BigUint::from(3u32));
+        let q = Point::Coor(BigUint::from(6u32), BigUint::from(4u32));
+
+        assert_eq!(ec.try_add(&p, &q), Err(Error::PointNotOnCurve));
+        assert_eq!(ec.try_double(&q), Err(Error::PointNotOnCurve));
+        assert_eq!(ec.try_scalar_mul(&q, &BigUint::from(2u32)), Err(Error::PointNotOnCurve));
+    }
+
+    #[test]
+    fn test_try_point_ops_unreduced_coordinates(){
+        //y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        // (5, 1 + 17) satisfies the equation mod 17 but is not reduced
+        let p = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+        let unreduced = Point::Coor(BigUint::from(5u32), BigUint::from(18u32));
+        assert!(ec.is_on_curve(&p));
+        assert!(!ec.is_on_curve(&unreduced));
+
+        let two = BigUint::from(2u32);
+        assert_eq!(ec.try_add(&p, &unreduced), Err(Error::PointNotOnCurve));
+        assert_eq!(ec.try_double(&unreduced), Err(Error::PointNotOnCurve));
+        assert_eq!(ec.try_scalar_mul(&unreduced, &two), Err(Error::PointNotOnCurve));
+        assert_eq!(ec.try_scalar_mul_ct(&unreduced, &two), Err(Error::PointNotOnCurve));
+        assert_eq!(ec.try_scalar_mul_wnaf(&unreduced, &two, 4), Err(Error::PointNotOnCurve));
+        let points = [p.clone(), unreduced.clone()];
+        let scalars = [two.clone(), two.clone()];
+        assert_eq!(ec.try_multi_scalar_mul(&points, &scalars), Err(Error::PointNotOnCurve));
+        assert_eq!(ec.try_multi_scalar_mul_pippenger(&points, &scalars), Err(Error::PointNotOnCurve));
+
+        // the ladder used to truncate an unreduced x of secp256k1 and return a wrong point
+        let ec = EllipticCurve{
+            a: BigUint::from(0u32),
+            b: BigUint::from(7u32),
+            p: BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F", 16).unwrap(),
+        };
+        let x = BigUint::parse_bytes(b"79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798", 16).unwrap();
+        let y = BigUint::parse_bytes(b"483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8", 16).unwrap();
+        let unreduced = Point::Coor(&x + &ec.p, y);
+        assert_eq!(ec.try_scalar_mul_ct(&unreduced, &two), Err(Error::PointNotOnCurve));
+    }
 }
\ No newline at end of file
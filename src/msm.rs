@@ -0,0 +1,215 @@
+use crate::jacobian::JacobianPoint;
+use crate::wnaf::{wnaf, DEFAULT_WINDOW};
+use crate::{EllipticCurve, Error, Point};
+use num_bigint::BigUint;
+
+impl EllipticCurve {
+    // Straus (Shamir's trick) with w-NAF digits: sum of scalars[i] * points[i]
+    // all products share a single chain of doublings, so n products cost about as many
+    // doublings as one scalar multiplication plus the additions of each w-NAF
+    // variable time, only for public scalars
+    pub(crate) fn multi_scalar_mul_jacobian(&self, points: &[JacobianPoint], scalars: &[BigUint]) -> JacobianPoint{
+        let tables: Vec<Vec<JacobianPoint>> = points.iter()
+            .map(|c| self.wnaf_table(c, DEFAULT_WINDOW))
+            .collect();
+        let digits: Vec<Vec<i64>> = scalars.iter()
+            .map(|k| wnaf(k, DEFAULT_WINDOW))
+            .collect();
+        let length = digits.iter().map(|d| d.len()).max().unwrap_or(0);
+
+        let mut t = JacobianPoint::identity();
+        for i in (0..length).rev() {
+            t = self.jacobian_double(&t);
+            for (table, naf) in tables.iter().zip(digits.iter()) {
+                match naf.get(i) {
+                    Some(&digit) if digit != 0 => {
+                        t = self.jacobian_add(&t, &self.wnaf_lookup(table, digit));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        t
+    }
+
+    // Pippenger's bucket method: sum of scalars[i] * points[i]
+    // the scalars are cut into windows of c bits, in every window each point is added once
+    // into the bucket of its digit and the buckets are combined with a running sum,
+    // so a window costs about n + 2^(c+1) additions instead of n scalar multiplications
+    // variable time, only for public scalars
+    pub(crate) fn pippenger_jacobian(&self, points: &[JacobianPoint], scalars: &[BigUint]) -> JacobianPoint{
+        let c = pippenger_window(points.len());
+        let bits = scalars.iter().map(|k| k.bits()).max().unwrap_or(0) as usize;
+        let windows = bits.div_ceil(c);
+
+        let mut t = JacobianPoint::identity();
+        for w in (0..windows).rev() {
+            for _ in 0..c {
+                t = self.jacobian_double(&t);
+            }
+            // buckets[d - 1] collects the points whose digit in this window is d
+            let mut buckets = vec![JacobianPoint::identity(); (1 << c) - 1];
+            for (point, scalar) in points.iter().zip(scalars.iter()) {
+                let digit = window_digit(scalar, w * c, c);
+                if digit != 0 {
+                    buckets[digit - 1] = self.jacobian_add(&buckets[digit - 1], point);
+                }
+            }
+            // sum of d * buckets[d - 1] = sum over d of (buckets[d - 1] + ... + buckets[top])
+            let mut running_sum = JacobianPoint::identity();
+            let mut window_sum = JacobianPoint::identity();
+            for bucket in buckets.iter().rev() {
+                running_sum = self.jacobian_add(&running_sum, bucket);
+                window_sum = self.jacobian_add(&window_sum, &running_sum);
+            }
+            t = self.jacobian_add(&t, &window_sum);
+        }
+        t
+    }
+
+    pub fn multi_scalar_mul_pippenger(&self, points: &[Point], scalars: &[BigUint]) -> Point{
+        self.try_multi_scalar_mul_pippenger(points, scalars).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_multi_scalar_mul_pippenger(&self, points: &[Point], scalars: &[BigUint]) -> Result<Point, Error>{
+        let points = self.check_msm_input(points, scalars)?;
+        let t = self.pippenger_jacobian(&points, scalars);
+        Ok(self.to_affine(&t))
+    }
+
+    fn check_msm_input(&self, points: &[Point], scalars: &[BigUint]) -> Result<Vec<JacobianPoint>, Error>{
+        if points.len() != scalars.len() {
+            return Err(Error::LengthMismatch);
+        }
+        if !points.iter().all(|c| self.is_on_curve(c)) {
+            return Err(Error::PointNotOnCurve);
+        }
+        Ok(points.iter().map(|c| self.to_jacobian(c)).collect())
+    }
+
+    pub fn multi_scalar_mul(&self, points: &[Point], scalars: &[BigUint]) -> Point{
+        self.try_multi_scalar_mul(points, scalars).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_multi_scalar_mul(&self, points: &[Point], scalars: &[BigUint]) -> Result<Point, Error>{
+        let points = self.check_msm_input(points, scalars)?;
+        let t = self.multi_scalar_mul_jacobian(&points, scalars);
+        Ok(self.to_affine(&t))
+    }
+}
+
+// window size in bits for n products, roughly ln(n) + 2
+// small inputs use 3 bits since the 2^c buckets would dominate otherwise
+fn pippenger_window(n: usize) -> usize{
+    if n < 32 {
+        3
+    } else {
+        // ln(n) = log2(n) * ln(2)
+        let log2 = (usize::BITS - n.leading_zeros()) as usize;
+        log2 * 69 / 100 + 2
+    }
+}
+
+// bits [start, start + c) of the scalar
+fn window_digit(scalar: &BigUint, start: usize, c: usize) -> usize{
+    let mut digit = 0;
+    for b in 0..c {
+        if scalar.bit((start + b) as u64) {
+            digit |= 1 << b;
+        }
+    }
+    digit
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_multi_scalar_mul(){
+        //y^2 = x^3 + 2x + 2 mod 17, the order of the group is 19
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let p = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+        let q = Point::Coor(BigUint::from(3u32), BigUint::from(16u32));
+        let r = Point::Coor(BigUint::from(6u32), BigUint::from(3u32));
+        let points = vec![p.clone(), q.clone(), r.clone(), Point::Identity];
+
+        for (a, b, c) in [(0u32, 0u32, 0u32), (1, 2, 3), (18, 19, 20), (123, 7, 1000), (5, 0, 14)] {
+            let scalars = vec![BigUint::from(a), BigUint::from(b), BigUint::from(c), BigUint::from(9u32)];
+            let mut expected = ec.scalar_mul(&p, &scalars[0]);
+            expected = ec.add(&expected, &ec.scalar_mul(&q, &scalars[1]));
+            expected = ec.add(&expected, &ec.scalar_mul(&r, &scalars[2]));
+            assert_eq!(ec.multi_scalar_mul(&points, &scalars), expected);
+        }
+
+        assert_eq!(ec.multi_scalar_mul(&[], &[]), Point::Identity);
+    }
+
+    #[test]
+    fn test_pippenger_window(){
+        assert_eq!(pippenger_window(1), 3);
+        assert_eq!(pippenger_window(31), 3);
+        assert!(pippenger_window(1024) > pippenger_window(32));
+        assert_eq!(window_digit(&BigUint::from(0b1101_0110u32), 4, 4), 0b1101);
+        assert_eq!(window_digit(&BigUint::from(0b1101_0110u32), 6, 4), 0b11);
+    }
+
+    #[test]
+    fn test_multi_scalar_mul_pippenger(){
+        //y^2 = x^3 + 2x + 2 mod 17, the order of the group is 19
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let g = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+
+        // n = 40 goes above the small input window size
+        for n in [0usize, 1, 2, 5, 40] {
+            let points: Vec<Point> = (0..n)
+                .map(|i| ec.scalar_mul(&g, &BigUint::from(i as u32 + 1)))
+                .collect();
+            let scalars: Vec<BigUint> = (0..n)
+                .map(|i| BigUint::from((i as u32 * 7919) % 1000))
+                .collect();
+
+            let mut expected = Point::Identity;
+            for (point, scalar) in points.iter().zip(scalars.iter()) {
+                expected = ec.add(&expected, &ec.scalar_mul(point, scalar));
+            }
+            assert_eq!(ec.multi_scalar_mul_pippenger(&points, &scalars), expected);
+            assert_eq!(ec.multi_scalar_mul(&points, &scalars), expected);
+        }
+    }
+
+    #[test]
+    fn test_multi_scalar_mul_errors(){
+        //y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let p = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+        let not_on_curve = Point::Coor(BigUint::from(5u32), BigUint::from(2u32));
+
+        let res = ec.try_multi_scalar_mul(&[p.clone(), p.clone()], &[BigUint::from(1u32)]);
+        assert_eq!(res, Err(Error::LengthMismatch));
+
+        let res = ec.try_multi_scalar_mul_pippenger(&[p.clone(), p.clone()], &[BigUint::from(1u32)]);
+        assert_eq!(res, Err(Error::LengthMismatch));
+
+        let res = ec.try_multi_scalar_mul(&[p.clone(), not_on_curve.clone()], &[BigUint::from(1u32), BigUint::from(1u32)]);
+        assert_eq!(res, Err(Error::PointNotOnCurve));
+
+        let res = ec.try_multi_scalar_mul_pippenger(&[p, not_on_curve], &[BigUint::from(1u32), BigUint::from(1u32)]);
+        assert_eq!(res, Err(Error::PointNotOnCurve));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::secret::wipe;
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use hmac::{Mac, SimpleHmac};
+use num_bigint::BigUint;
+use zeroize::Zeroizing;
+
+// domain separation tags, passed as the additional data k' of RFC 6979 section 3.6
+// so that k and l come from two independent HMAC-DRBG instances
+pub(crate) const NONCE_K_TAG: &[u8] = b"ElGamalLike-nonce-k";
+pub(crate) const NONCE_L_TAG: &[u8] = b"ElGamalLike-nonce-l";
+
+// deterministic nonce in [1, q) derived from the private key and the (already reduced) hash
+// HMAC-DRBG with the message digest D as in https://www.rfc-editor.org/rfc/rfc6979#section-3.2
+// the encoded private key and the DRBG state K, V are wiped on drop
+pub(crate) fn rfc6979_nonce<D: Digest + BlockSizeUser>(q: &BigUint, priv_key: &BigUint, hash: &BigUint, extra: &[u8]) -> BigUint{
+    let qlen = q.bits() as usize;
+    let rlen = qlen.div_ceil(8);
+    let x = Zeroizing::new(int2octets(priv_key, rlen));
+    let h1 = int2octets(&(hash % q), rlen);
+
+    // steps b. to g.
+    let mut v = Zeroizing::new(vec![0x01u8; <D as Digest>::output_size()]);
+    let mut k = Zeroizing::new(vec![0x00u8; <D as Digest>::output_size()]);
+    for separator in [0x00u8, 0x01u8] {
+        k = hmac::<D>(&k, &[&v, &[separator], &x, &h1, extra]);
+        v = hmac::<D>(&k, &[&v]);
+    }
+
+    // step h.
+    loop {
+        let mut t = Zeroizing::new(Vec::with_capacity(rlen));
+        while t.len() < rlen {
+            v = hmac::<D>(&k, &[&v]);
+            t.extend_from_slice(&v);
+        }
+        let mut nonce = bits2int(&t, qlen);
+        if nonce != BigUint::from(0u32) && nonce < *q {
+            return nonce;
+        }
+        wipe(&mut nonce);
+        k = hmac::<D>(&k, &[&v, &[0x00]]);
+        v = hmac::<D>(&k, &[&v]);
+    }
+}
+
+fn hmac<D: Digest + BlockSizeUser>(key: &[u8], data: &[&[u8]]) -> Zeroizing<Vec<u8>>{
+    let mut mac = SimpleHmac::<D>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    Zeroizing::new(mac.finalize().into_bytes().to_vec())
+}
+
+// big-endian encoding of x on exactly rlen bytes
+pub(crate) fn int2octets(x: &BigUint, rlen: usize) -> Vec<u8>{
+    let bytes = x.to_bytes_be();
+    let mut out = vec![0u8; rlen.saturating_sub(bytes.len())];
+    out.extend_from_slice(&bytes[bytes.len().saturating_sub(rlen)..]);
+    out
+}
+
+// the leftmost qlen bits of the bytes as a big-endian integer
+pub(crate) fn bits2int(bytes: &[u8], qlen: usize) -> BigUint{
+    let x = BigUint::from_bytes_be(bytes);
+    let blen = bytes.len() * 8;
+    if blen > qlen {
+        x >> (blen - qlen)
+    } else {
+        x
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+    use sha2::Sha256;
+
+    #[test]
+    fn test_rfc6979_p256_sha256_sample(){
+        // https://www.rfc-editor.org/rfc/rfc6979#appendix-A.2.5 with message "sample"
+        let q = BigUint::parse_bytes(
+            b"FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551",
+            16
+        ).unwrap();
+        let x = BigUint::parse_bytes(
+            b"C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721",
+            16
+        ).unwrap();
+        // SHA-256("sample")
+        let h1 = BigUint::parse_bytes(
+            b"AF2BDBE1AA9B6EC1E2ADE1D694F41FC71A831D0268E9891562113D8A62ADD1BF",
+            16
+        ).unwrap();
+        let k = BigUint::parse_bytes(
+            b"A6E3C57DD01ABE90086538398355DD4C3B17AA873382B0F24D6129493D8AAD60",
+            16
+        ).unwrap();
+
+        assert_eq!(rfc6979_nonce::<Sha256>(&q, &x, &h1, &[]), k);
+    }
+
+    #[test]
+    fn test_nonce_domain_separation(){
+        // https://arxiv.org/ftp/arxiv/papers/1301/1301.2335.pdf example 5.1.
+        let q = BigUint::from(113u32);
+        let priv_key = BigUint::from(78u32);
+        let hash = BigUint::from(56u32);
+
+        let k = rfc6979_nonce::<Sha256>(&q, &priv_key, &hash, NONCE_K_TAG);
+        let l = rfc6979_nonce::<Sha256>(&q, &priv_key, &hash, NONCE_L_TAG);
+
+        assert!(k >= BigUint::from(1u32) && k < q);
+        assert!(l >= BigUint::from(1u32) && l < q);
+        assert_eq!(k, rfc6979_nonce::<Sha256>(&q, &priv_key, &hash, NONCE_K_TAG));
+    }
+
+    #[test]
+    fn test_int2octets_bits2int(){
+        assert_eq!(int2octets(&BigUint::from(0x0102u32), 4), vec![0, 0, 1, 2]);
+        assert_eq!(bits2int(&[0xff, 0x00], 12), BigUint::from(0xff0u32));
+        assert_eq!(bits2int(&[0x01, 0x00], 16), BigUint::from(0x100u32));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::Error;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use zeroize::Zeroizing;
+
+// PEM armor of RFC 7468: the base64 of the DER in lines of 64 characters between
+// -----BEGIN label----- and -----END label-----
+const LINE_WIDTH: usize = 64;
+
+// the der and its base64 may hold a private key: body is wiped and out is allocated at
+// its final size, so neither leaves a copy behind
+pub(crate) fn encode(label: &str, der: &[u8]) -> String{
+    let body = Zeroizing::new(STANDARD.encode(der));
+    let begin = format!("-----BEGIN {}-----\n", label);
+    let end = format!("-----END {}-----\n", label);
+    let lines = body.len().div_ceil(LINE_WIDTH);
+    let mut out = String::with_capacity(begin.len() + body.len() + lines + end.len());
+    out.push_str(&begin);
+    for line in body.as_bytes().chunks(LINE_WIDTH) {
+        out.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        out.push('\n');
+    }
+    out.push_str(&end);
+    out
+}
+
+// the label has to match, whitespace around the armor and in the body (\r\n line ends,
+// a missing final newline, ...) is ignored
+pub(crate) fn decode(label: &str, pem: &str) -> Result<Vec<u8>, Error>{
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let body = pem.trim()
+        .strip_prefix(&begin)
+        .and_then(|rest| rest.strip_suffix(&end))
+        .ok_or(Error::InvalidEncoding)?;
+    // the base64 of a private key, wiped once decoded and allocated once like in encode
+    let mut base64 = Zeroizing::new(String::with_capacity(body.len()));
+    base64.extend(body.chars().filter(|c| !c.is_ascii_whitespace()));
+    STANDARD.decode(&*base64).map_err(|_| Error::InvalidEncoding)
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_pem(){
+        let der: Vec<u8> = (0..100u8).collect();
+        let pem = encode("PUBLIC KEY", &der);
+
+        assert_eq!(pem.capacity(), pem.len());
+        let lines: Vec<&str> = pem.lines().collect();
+        assert_eq!(lines[0], "-----BEGIN PUBLIC KEY-----");
+        assert_eq!(lines[1].len(), 64);
+        assert_eq!(lines[4], "-----END PUBLIC KEY-----");
+        assert_eq!(decode("PUBLIC KEY", &pem), Ok(der.clone()));
+
+        let crlf = pem.replace('\n', "\r\n");
+        assert_eq!(decode("PUBLIC KEY", crlf.trim_end()), Ok(der));
+
+        assert_eq!(decode("PRIVATE KEY", &pem), Err(Error::InvalidEncoding));
+        assert_eq!(decode("PUBLIC KEY", &pem.replace('A', "*")), Err(Error::InvalidEncoding));
+    }
+}
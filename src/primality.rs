@@ -0,0 +1,85 @@
+use num_bigint::{BigUint, RandBigInt};
+
+// the first twelve primes, used for trial division and as the fixed Miller-Rabin bases,
+// which alone already decide primality of every n below 3.3 * 10^24
+const SMALL_PRIMES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// rounds with random bases on top of the fixed ones, a composite passes a round with
+// probability at most 1/4
+const RANDOM_ROUNDS: usize = 20;
+
+// Miller-Rabin probabilistic primality test
+pub(crate) fn is_probable_prime(n: &BigUint) -> bool{
+    if *n < BigUint::from(2u32) {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        if *n == BigUint::from(p) {
+            return true;
+        }
+        if (n % p) == BigUint::from(0u32) {
+            return false;
+        }
+    }
+
+    // n - 1 = 2^s * d with d odd
+    let one = BigUint::from(1u32);
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().expect("n - 1 is not zero");
+    let d = &n_minus_one >> s;
+
+    // true when a proves that n is composite
+    let is_witness = |a: &BigUint| -> bool {
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus_one {
+            return false;
+        }
+        for _ in 1..s {
+            x = x.modpow(&BigUint::from(2u32), n);
+            if x == n_minus_one {
+                return false;
+            }
+        }
+        true
+    };
+
+    // n > 37 here, so every fixed base is in [2, n - 1)
+    if SMALL_PRIMES.iter().any(|&a| is_witness(&BigUint::from(a))) {
+        return false;
+    }
+    let mut rng = rand::thread_rng();
+    let two = BigUint::from(2u32);
+    (0..RANDOM_ROUNDS).all(|_| !is_witness(&rng.gen_biguint_range(&two, &n_minus_one)))
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_is_probable_prime(){
+        let primes: Vec<u32> = (0..1000u32)
+            .filter(|&n| n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0))
+            .collect();
+        for n in 0..1000u32 {
+            assert_eq!(is_probable_prime(&BigUint::from(n)), primes.contains(&n), "{}", n);
+        }
+
+        // Carmichael numbers fool the Fermat test but not Miller-Rabin
+        for n in [561u32, 1105, 1729, 2465, 2821, 6601, 8911] {
+            assert!(!is_probable_prime(&BigUint::from(n)));
+        }
+        // 3825123056546413051 = 149491 * 747451 * 34233211 is a strong pseudoprime to every prime base
+        // up to 31, only the base 37 reveals it
+        assert!(!is_probable_prime(&BigUint::from(3825123056546413051u64)));
+
+        // 2^127 - 1 is a Mersenne prime, 2^128 + 1 is not (59649589127497217 divides it)
+        let two = BigUint::from(2u32);
+        assert!(is_probable_prime(&(two.pow(127) - BigUint::from(1u32))));
+        assert!(!is_probable_prime(&(two.pow(128) + BigUint::from(1u32))));
+        // the secp256k1 order and its product with a prime
+        let q = crate::curves::secp256k1().order;
+        assert!(is_probable_prime(&q));
+        assert!(!is_probable_prime(&(&q * BigUint::from(757u32))));
+    }
+}
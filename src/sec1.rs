@@ -0,0 +1,165 @@
+use crate::nonce::int2octets;
+use crate::{EllipticCurve, Error, FiniteField, Point};
+use num_bigint::BigUint;
+
+// SEC1 point encoding, https://www.secg.org/sec1-v2.pdf sections 2.3.3 and 2.3.4
+// identity     => 0x00
+// uncompressed => 0x04 || x || y
+// compressed   => 0x02 || x when y is even, 0x03 || x when y is odd
+// x and y are big-endian on exactly field_size() bytes
+const IDENTITY_TAG: u8 = 0x00;
+const COMPRESSED_EVEN_TAG: u8 = 0x02;
+const COMPRESSED_ODD_TAG: u8 = 0x03;
+const UNCOMPRESSED_TAG: u8 = 0x04;
+
+impl EllipticCurve {
+    // length in bytes of a field element
+    pub fn field_size(&self) -> usize{
+        (self.p.bits() as usize).div_ceil(8)
+    }
+
+    pub fn encode_point(&self, c: &Point, compressed: bool) -> Vec<u8>{
+        self.try_encode_point(c, compressed).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_encode_point(&self, c: &Point, compressed: bool) -> Result<Vec<u8>, Error>{
+        if !self.is_on_curve(c) {
+            return Err(Error::PointNotOnCurve);
+        }
+        match c {
+            Point::Identity => Ok(vec![IDENTITY_TAG]),
+            Point::Coor(x, y) => {
+                let size = self.field_size();
+                let mut out = Vec::with_capacity(1 + 2 * size);
+                if compressed {
+                    out.push(if y.bit(0) { COMPRESSED_ODD_TAG } else { COMPRESSED_EVEN_TAG });
+                    out.extend(int2octets(x, size));
+                } else {
+                    out.push(UNCOMPRESSED_TAG);
+                    out.extend(int2octets(x, size));
+                    out.extend(int2octets(y, size));
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    // the decoded point is always on the curve, coordinates must be below p
+    pub fn decode_point(&self, bytes: &[u8]) -> Result<Point, Error>{
+        let size = self.field_size();
+        let (&tag, rest) = bytes.split_first().ok_or(Error::InvalidEncoding)?;
+        let point = match tag {
+            IDENTITY_TAG if rest.is_empty() => Point::Identity,
+            UNCOMPRESSED_TAG if rest.len() == 2 * size => {
+                let x = self.decode_field_element(&rest[..size])?;
+                let y = self.decode_field_element(&rest[size..])?;
+                Point::Coor(x, y)
+            }
+            COMPRESSED_EVEN_TAG | COMPRESSED_ODD_TAG if rest.len() == size => {
+                let x = self.decode_field_element(rest)?;
+                let y = self.decompress_y(&x, tag == COMPRESSED_ODD_TAG)?;
+                Point::Coor(x, y)
+            }
+            _ => return Err(Error::InvalidEncoding),
+        };
+        if !self.is_on_curve(&point) {
+            return Err(Error::PointNotOnCurve);
+        }
+        Ok(point)
+    }
+
+    fn decode_field_element(&self, bytes: &[u8]) -> Result<BigUint, Error>{
+        let x = BigUint::from_bytes_be(bytes);
+        if x >= self.p {
+            return Err(Error::FieldElementOutOfRange);
+        }
+        Ok(x)
+    }
+
+    // y with the requested parity such that y^2 = x^3 + ax + b
+    fn decompress_y(&self, x: &BigUint, odd: bool) -> Result<BigUint, Error>{
+        let x3 = x.modpow(&BigUint::from(3u32), &self.p);
+        let ax = FiniteField::mul(x, &self.a, &self.p);
+        let mut rhs = FiniteField::add(&x3, &ax, &self.p);
+        rhs = FiniteField::add(&rhs, &self.b, &self.p);
+
+        let y = FiniteField::sqrt(&rhs, &self.p).ok_or(Error::PointNotOnCurve)?;
+        if y.bit(0) == odd {
+            return Ok(y);
+        }
+        // y = 0 has no odd counterpart
+        let y = FiniteField::inv_add(&y, &self.p);
+        if y.bit(0) != odd {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(y)
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+    use num_bigint::RandBigInt;
+
+    #[test]
+    fn test_sec1_secp256k1(){
+        let secp256k1 = crate::curves::secp256k1();
+        let ec = secp256k1.curve;
+        let n = secp256k1.order;
+        let g = secp256k1.generator;
+
+        // the well-known encodings of the secp256k1 generator
+        let compressed = hex::decode("0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798").unwrap();
+        let uncompressed = hex::decode(
+            "0479BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798\
+             483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8"
+        ).unwrap();
+        assert_eq!(ec.encode_point(&g, true), compressed);
+        assert_eq!(ec.encode_point(&g, false), uncompressed);
+        assert_eq!(ec.decode_point(&compressed), Ok(g.clone()));
+        assert_eq!(ec.decode_point(&uncompressed), Ok(g.clone()));
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let c = ec.scalar_mul(&g, &rng.gen_biguint_range(&BigUint::from(1u32), &n));
+            assert_eq!(ec.decode_point(&ec.encode_point(&c, true)), Ok(c.clone()));
+            assert_eq!(ec.decode_point(&ec.encode_point(&c, false)), Ok(c));
+        }
+
+        assert_eq!(ec.encode_point(&Point::Identity, true), vec![0x00]);
+        assert_eq!(ec.decode_point(&[0x00]), Ok(Point::Identity));
+
+        // x = 5 gives 5^3 + 7 = 132, which is not a square mod p
+        let mut no_root = vec![0x02];
+        no_root.extend(int2octets(&BigUint::from(5u32), 32));
+        assert_eq!(ec.decode_point(&no_root), Err(Error::PointNotOnCurve));
+    }
+
+    #[test]
+    fn test_sec1_rejects_malformed_input(){
+        let toy757 = crate::curves::toy757();
+        let (ec, g) = (toy757.curve, toy757.generator);
+
+        assert_eq!(ec.field_size(), 2);
+        let encoded = ec.encode_point(&g, false);
+        assert_eq!(encoded, vec![0x04, 0x02, 0x11, 0x02, 0x36]);
+        assert_eq!(ec.decode_point(&encoded), Ok(g.clone()));
+
+        assert_eq!(ec.decode_point(&[]), Err(Error::InvalidEncoding));
+        assert_eq!(ec.decode_point(&[0x00, 0x00]), Err(Error::InvalidEncoding));
+        assert_eq!(ec.decode_point(&encoded[..4]), Err(Error::InvalidEncoding));
+        assert_eq!(ec.decode_point(&[0x05, 0x02, 0x11, 0x02, 0x36]), Err(Error::InvalidEncoding));
+        // 0x02f5 = 757 = p
+        assert_eq!(ec.decode_point(&[0x04, 0x02, 0xf5, 0x02, 0x36]), Err(Error::FieldElementOutOfRange));
+        assert_eq!(ec.decode_point(&[0x04, 0x02, 0x11, 0x02, 0x37]), Err(Error::PointNotOnCurve));
+
+        // 757 = 1 mod 4, decompression goes through Tonelli-Shanks
+        assert_eq!(ec.encode_point(&g, true), vec![0x02, 0x02, 0x11]);
+        assert_eq!(ec.decode_point(&[0x02, 0x02, 0x11]), Ok(g.clone()));
+        let minus_g = ec.negate(&g);
+        assert_eq!(ec.decode_point(&ec.encode_point(&minus_g, true)), Ok(minus_g));
+
+        let not_on_curve = Point::Coor(BigUint::from(1u32), BigUint::from(1u32));
+        assert_eq!(ec.try_encode_point(&not_on_curve, true), Err(Error::PointNotOnCurve));
+    }
+}
@@ -0,0 +1,52 @@
+use num_bigint::BigUint;
+use std::ops::Deref;
+
+// overwrites the digits of a secret BigUint before its buffer is freed
+// BigUint gives no mutable access to its digits, but assign_from_slice writes into the
+// existing allocation when the new value has the same length, so x is first overwritten
+// with ones (black_box keeps the compiler from dropping these writes) and then reset to 0
+// best effort only: copies left behind by reallocations and by the temporaries of the
+// arithmetic (e.g. the product before the reduction mod q) are not reached
+pub(crate) fn wipe(x: &mut BigUint){
+    let digits = x.bits().div_ceil(32) as usize;
+    x.assign_from_slice(&vec![u32::MAX; digits]);
+    std::hint::black_box(&*x);
+    *x = BigUint::from(0u32);
+}
+
+// a scalar that is wiped when it goes out of scope: the nonces k and l and the
+// intermediate values of sign
+pub(crate) struct SecretScalar(pub(crate) BigUint);
+
+impl Deref for SecretScalar {
+    type Target = BigUint;
+
+    fn deref(&self) -> &BigUint{
+        &self.0
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self){
+        wipe(&mut self.0);
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_wipe(){
+        let mut x = BigUint::parse_bytes(
+            b"C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721",
+            16
+        ).unwrap();
+        wipe(&mut x);
+        assert_eq!(x, BigUint::from(0u32));
+
+        let mut zero = BigUint::from(0u32);
+        wipe(&mut zero);
+        assert_eq!(zero, BigUint::from(0u32));
+    }
+}
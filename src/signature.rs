@@ -0,0 +1,853 @@
+use crate::fixed_base::FixedBaseTable;
+use crate::jacobian::JacobianPoint;
+use crate::keys::{Nonce, PrivateKey, PublicKey, Signature};
+use crate::nonce::{bits2int, rfc6979_nonce, NONCE_K_TAG, NONCE_L_TAG};
+use crate::primality::is_probable_prime;
+use crate::secret::SecretScalar;
+use crate::{EllipticCurve, Error, FiniteField, ParameterError, Point};
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use num_bigint::{BigUint, RandBigInt};
+use sha2::Sha256;
+use std::io::Read;
+use std::marker::PhantomData;
+
+// batches with more products than this use Pippenger instead of Straus
+const PIPPENGER_THRESHOLD: usize = 256;
+
+// chunk size used to hash readers
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+// ElGamal-like signature over a generic elliptic curve
+// protocol => https://arxiv.org/ftp/arxiv/papers/1301/1301.2335.pdf
+// ec is the curve, gen is the generator point and q is the order of gen
+// gen_table holds precomputed multiples of gen, it is built once in new and
+// shared by key generation and signing (R = kG, S = lG)
+// D is the message digest (any RustCrypto digest::Digest such as sha2::Sha384 or sha3::Sha3_256),
+// it hashes the messages and drives the HMAC-DRBG of the deterministic nonces
+// subgroup_check is set when the curve may have a cofactor, points of keys and signatures
+// then also have to satisfy qP = O (see has_cofactor_one)
+pub struct ElGamallikeSignature<D = Sha256>{
+    ec: EllipticCurve,
+    gen: Point,
+    q: BigUint,
+    gen_table: FixedBaseTable,
+    subgroup_check: bool,
+    digest: PhantomData<fn() -> D>,
+}
+
+// new and with_digest trust the domain parameters, try_new and try_with_digest check them
+// first, which costs a few Miller-Rabin tests and a scalar multiplication
+impl ElGamallikeSignature<Sha256> {
+    pub fn new(ec: EllipticCurve, gen: Point, q: BigUint) -> Self{
+        Self::with_digest(ec, gen, q)
+    }
+
+    pub fn try_new(ec: EllipticCurve, gen: Point, q: BigUint) -> Result<Self, Error>{
+        Self::try_with_digest(ec, gen, q)
+    }
+}
+
+impl<D: Digest + BlockSizeUser> ElGamallikeSignature<D> {
+    // ElGamallikeSignature::<Sha512>::with_digest(ec, gen, q)
+    pub fn with_digest(ec: EllipticCurve, gen: Point, q: BigUint) -> Self{
+        let gen_table = FixedBaseTable::new(&ec, &gen);
+        let subgroup_check = !has_cofactor_one(&ec, &q);
+        ElGamallikeSignature { ec, gen, q, gen_table, subgroup_check, digest: PhantomData }
+    }
+
+    pub fn try_with_digest(ec: EllipticCurve, gen: Point, q: BigUint) -> Result<Self, Error>{
+        check_domain_parameters(&ec, &gen, &q)?;
+        Ok(Self::with_digest(ec, gen, q))
+    }
+}
+
+// the curve has at most p + 1 + 2 sqrt(p) points (Hasse), so when twice the order q is above
+// that bound the subgroup generated by G is the whole curve and every point on the curve
+// has order q, as for secp256k1 and the NIST curves
+// on curves with a cofactor (the toy curve of the paper has 7) a point on the curve may have a
+// small order component that the verification equation does not see
+fn has_cofactor_one(ec: &EllipticCurve, q: &BigUint) -> bool{
+    let hasse_bound = &ec.p + BigUint::from(1u32) + BigUint::from(2u32) * (ec.p.sqrt() + BigUint::from(1u32));
+    BigUint::from(2u32) * q > hasse_bound
+}
+
+// the curve passes EllipticCurve::validate, gen is a point of the curve other than the
+// identity, q is prime and qG = O, so that gen has order exactly q
+fn check_domain_parameters(ec: &EllipticCurve, gen: &Point, q: &BigUint) -> Result<(), Error>{
+    ec.validate()?;
+    match gen {
+        Point::Identity => return Err(ParameterError::IdentityGenerator.into()),
+        Point::Coor(..) if !ec.is_on_curve(gen) => {
+            return Err(ParameterError::GeneratorNotOnCurve.into());
+        }
+        Point::Coor(..) => {}
+    }
+    if !is_probable_prime(q) {
+        return Err(ParameterError::OrderNotPrime.into());
+    }
+    if ec.scalar_mul(gen, q) != Point::Identity {
+        return Err(ParameterError::WrongOrder.into());
+    }
+    Ok(())
+}
+
+impl<D> ElGamallikeSignature<D> {
+    pub fn curve(&self) -> &EllipticCurve{
+        &self.ec
+    }
+
+    pub fn generator(&self) -> &Point{
+        &self.gen
+    }
+
+    pub fn order(&self) -> &BigUint{
+        &self.q
+    }
+
+    pub fn generate_key_pair(&self) -> (PrivateKey,PublicKey){
+        let priv_key = self.generate_private_key();
+        let pub_key = self.generate_pub_key(&priv_key);
+        (priv_key,pub_key)
+    }
+
+    pub fn generate_private_key(&self) -> PrivateKey{
+        PrivateKey { scalar: self.generate_random_number_in_range(&self.q) }
+    }
+
+    // a random nonce for sign, sign_deterministic needs none
+    pub fn generate_nonce(&self) -> Nonce{
+        Nonce { scalar: self.generate_random_number_in_range(&self.q) }
+    }
+
+    // the private key is in [1, q) so B = aG is never the identity
+    pub fn generate_pub_key(&self, priv_key: &PrivateKey) -> PublicKey{
+        PublicKey { point: self.mul_gen(&priv_key.scalar) }
+    }
+
+    // k * gen with the precomputed table, safe for secret scalars
+    fn mul_gen(&self, exponent: &BigUint) -> Point{
+        self.ec.to_affine(&self.gen_table.mul(&self.ec, exponent))
+    }
+
+    pub fn generate_random_number_in_range(&self, max:&BigUint) -> BigUint{
+        let mut random_number_generator = rand::thread_rng();
+        random_number_generator.gen_biguint_range(&BigUint::from(1u32), max)
+    }
+
+    // SIGNING PART
+    // protocol => https://arxiv.org/ftp/arxiv/papers/1301/1301.2335.pdf
+    //signing process is as follows:
+    // choose an random k and l
+    // compute R = kG  and S = lG
+    // compute t = sk + rl + ma  mod q , where a is the private key and m is the message(hashed) and q is the order of the curve
+    // and s is the x coordinate of S
+    // r is the x coordinate of R
+    // k and l are Nonce values, so they cannot be swapped with the hash
+
+    pub fn sign(
+        &self,
+        hash: &BigUint,
+        priv_key: &PrivateKey,
+        random_k: &Nonce,
+        random_l: &Nonce
+    ) -> Result<Signature, Error> {
+        self.check_scalar(hash)?;
+        // a key or a nonce of another scheme may not fit in [1, q)
+        self.check_nonzero_scalar(&priv_key.scalar)?;
+        self.check_nonzero_scalar(&random_k.scalar)?;
+        self.check_nonzero_scalar(&random_l.scalar)?;
+        let (random_k, random_l) = (&random_k.scalar, &random_l.scalar);
+
+        // k and l are secret, the table lookups do not depend on their bits
+        let r_point = self.mul_gen(random_k);
+        let s_point = self.mul_gen(random_l);
+
+        if let Point::Coor(r, _ ) = &r_point {
+            if let Point::Coor(s,_ ) = &s_point {
+                // sk, rl and ma each reveal a secret, they are wiped when dropped
+                let sk = SecretScalar(FiniteField::mul(s, random_k, &self.q));
+                let rl = SecretScalar(FiniteField::mul(r, random_l, &self.q));
+                let sk_rl = SecretScalar(FiniteField::add(&sk, &rl, &self.q));
+                let ma = SecretScalar(FiniteField::mul(hash, &priv_key.scalar, &self.q));
+                let t = FiniteField::add(&sk_rl, &ma, &self.q);
+                return Ok(Signature { r_point, s_point, t });
+            }
+        }
+        Err(Error::IdentityInSignature)
+    }
+
+    // VERIFICATION PART
+    // protocol => https://arxiv.org/ftp/arxiv/papers/1301/1301.2335.pdf
+    //verification process is as follows:
+    // recall the signature (R,S,t) where R,S are points and t is scalar
+    // compute tG, sR, rS and mB where m is message(hash) and B is the public key
+    // Signature is verified if tg == sR + rS + mB
+    // the four products are evaluated at once as tG + s(-R) + r(-S) + m(-B) == identity
+
+    pub fn verify(
+        &self,
+        hash: &BigUint,
+        pub_key: &PublicKey,
+        signature: &Signature
+    ) -> Result<bool, Error> {
+        let (points, scalars) = self.verification_terms(hash, pub_key, signature)?;
+        // all scalars here are public, so the faster variable-time path is fine
+        let res = self.ec.multi_scalar_mul_jacobian(&points, &scalars);
+        Ok(res.is_identity())
+    }
+
+    // BATCH VERIFICATION
+    // every signature i satisfies t_i G + s_i (-R_i) + r_i (-S_i) + m_i (-B_i) == identity
+    // with random 128-bit weights z_i the batch checks the single equation
+    // (sum z_i t_i) G + sum (z_i s_i (-R_i) + z_i r_i (-S_i) + z_i m_i (-B_i)) == identity
+    // an invalid signature passes only if its weight cancels the error, which happens with
+    // probability about 2^-128 (or 1/q for groups smaller than 2^128, where the weights are
+    // drawn below q)
+    // this relies on R_i, S_i and B_i having order q: on a curve with a cofactor an error of
+    // small order h cancels for one weight in h, which is why check_signature_parts and
+    // check_public_key reject points outside the subgroup there
+    // Ok(()) if every signature is valid, otherwise Error::InvalidSignatures with the indices
+    // of the malformed and invalid signatures, found by verifying them one by one
+
+    pub fn verify_batch(
+        &self,
+        batch: &[(&BigUint, &PublicKey, &Signature)]
+    ) -> Result<(), Error> {
+        let mut rng = rand::thread_rng();
+        let weight_bound = std::cmp::min(BigUint::from(1u32) << 128, self.q.clone());
+        let mut invalid = Vec::new();
+        let mut valid = Vec::new();
+        let mut points = vec![self.ec.to_jacobian(&self.gen)];
+        let mut scalars = vec![BigUint::from(0u32)];
+
+        for (i, (hash, pub_key, signature)) in batch.iter().enumerate() {
+            match self.verification_terms(hash, pub_key, signature) {
+                Ok((terms_points, terms_scalars)) => {
+                    let z = rng.gen_biguint_range(&BigUint::from(1u32), &weight_bound);
+                    let [_, r_point, s_point, pub_key] = terms_points;
+                    let [t, s, r, m] = terms_scalars;
+                    scalars[0] = FiniteField::add(&scalars[0], &FiniteField::mul(&z, &t, &self.q), &self.q);
+                    points.extend([r_point, s_point, pub_key]);
+                    scalars.extend([&z * s, &z * r, &z * m]);
+                    valid.push(i);
+                }
+                Err(_) => invalid.push(i),
+            }
+        }
+
+        let res = if points.len() > PIPPENGER_THRESHOLD {
+            self.ec.pippenger_jacobian(&points, &scalars)
+        } else {
+            self.ec.multi_scalar_mul_jacobian(&points, &scalars)
+        };
+
+        if !res.is_identity() {
+            // at least one signature is wrong, find out which ones
+            for i in valid {
+                let (hash, pub_key, signature) = batch[i];
+                if !self.verify(hash, pub_key, signature)? {
+                    invalid.push(i);
+                }
+            }
+            invalid.sort_unstable();
+        }
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignatures(invalid))
+        }
+    }
+
+    // validates the inputs of the verification equation and returns its four terms
+    // G, -R, -S, -B with the scalars t, s, r, m
+    // the key and the signature are checked again since they may come from another scheme
+    fn verification_terms(
+        &self,
+        hash: &BigUint,
+        pub_key: &PublicKey,
+        signature: &Signature
+    ) -> Result<([JacobianPoint; 4], [BigUint; 4]), Error> {
+        self.check_scalar(hash)?;
+        self.check_public_key(&pub_key.point)?;
+
+        let Signature { r_point, s_point, t } = signature;
+        self.check_signature_parts(r_point, s_point, t)?;
+
+        if let Point::Coor(r, _ ) = &r_point {
+            if let Point::Coor(s, _) = &s_point  {
+                // every point is already checked to be on the curve, so the whole
+                // computation stays in Jacobian coordinates without any inversion
+                let points = [
+                    self.ec.to_jacobian(&self.gen),
+                    self.ec.to_jacobian(&self.ec.negate(r_point)),
+                    self.ec.to_jacobian(&self.ec.negate(s_point)),
+                    self.ec.to_jacobian(&self.ec.negate(&pub_key.point)),
+                ];
+                let scalars = [t.clone(), s.clone(), r.clone(), hash.clone()];
+                return Ok((points, scalars));
+            }
+        }
+        Err(Error::IdentityInSignature)
+    }
+
+    fn check_scalar(&self, scalar: &BigUint) -> Result<(), Error>{
+        if *scalar >= self.q {
+            return Err(Error::ScalarOutOfRange);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_nonzero_scalar(&self, scalar: &BigUint) -> Result<(), Error>{
+        if *scalar == BigUint::from(0u32) {
+            return Err(Error::ScalarOutOfRange);
+        }
+        self.check_scalar(scalar)
+    }
+
+    pub(crate) fn check_public_key(&self, pub_key: &Point) -> Result<(), Error>{
+        self.check_coordinates(pub_key)?;
+        if !self.ec.is_on_curve(pub_key) {
+            return Err(Error::PointNotOnCurve);
+        }
+        if *pub_key == Point::Identity {
+            return Err(Error::IdentityPublicKey);
+        }
+        self.check_subgroup(pub_key)
+    }
+
+    pub(crate) fn check_signature_parts(&self, r_point: &Point, s_point: &Point, t: &BigUint) -> Result<(), Error>{
+        self.check_scalar(t)?;
+        self.check_coordinates(r_point)?;
+        self.check_coordinates(s_point)?;
+        if !self.ec.is_on_curve(r_point) || !self.ec.is_on_curve(s_point) {
+            return Err(Error::PointNotOnCurve);
+        }
+        if *r_point == Point::Identity || *s_point == Point::Identity {
+            return Err(Error::IdentityInSignature);
+        }
+        self.check_subgroup(r_point)?;
+        self.check_subgroup(s_point)
+    }
+
+    // (x + p, y) satisfies the curve equation but is not a valid encoding of (x, y), and the
+    // field arithmetic of the verification (negate, subs) expects reduced coordinates
+    fn check_coordinates(&self, point: &Point) -> Result<(), Error>{
+        match point {
+            Point::Coor(x, y) if *x >= self.ec.p || *y >= self.ec.p => Err(Error::FieldElementOutOfRange),
+            _ => Ok(()),
+        }
+    }
+
+    // qP = O, only computed when the curve may have a cofactor
+    fn check_subgroup(&self, point: &Point) -> Result<(), Error>{
+        if self.subgroup_check && !self.ec.scalar_mul_jacobian(&self.ec.to_jacobian(point), &self.q).is_identity() {
+            return Err(Error::PointNotInSubgroup);
+        }
+        Ok(())
+    }
+}
+
+impl<D: Digest + BlockSizeUser> ElGamallikeSignature<D> {
+    // deterministic signing, no RNG involved
+    // k and l are derived from the private key and the hash with HMAC-DRBG (RFC 6979),
+    // each with its own domain separation tag, so the same (hash, priv_key) always gives
+    // the same signature and a different hash gives unrelated nonces
+    pub fn sign_deterministic(
+        &self,
+        hash: &BigUint,
+        priv_key: &PrivateKey
+    ) -> Result<Signature, Error> {
+        self.check_scalar(hash)?;
+        self.check_nonzero_scalar(&priv_key.scalar)?;
+        let random_k = Nonce { scalar: rfc6979_nonce::<D>(&self.q, &priv_key.scalar, hash, NONCE_K_TAG) };
+        let random_l = Nonce { scalar: rfc6979_nonce::<D>(&self.q, &priv_key.scalar, hash, NONCE_L_TAG) };
+        self.sign(hash, priv_key, &random_k, &random_l)
+    }
+
+    // MESSAGE HASHING
+    // bytes-to-scalar mapping used by sign_message and verify_message:
+    // e = D(message) read as a big-endian integer of 8 * D::output_size() bits
+    // if q has fewer bits, e keeps only its leftmost q.bits() bits (e >> (8 * D::output_size() - q.bits()))
+    // m = e mod q
+    // this is the same conversion as bits2int in RFC 6979 (and the hash truncation of ECDSA)
+    pub fn hash_message(&self, message: &[u8]) -> BigUint{
+        self.hash_digest(D::new_with_prefix(message))
+    }
+
+    // same mapping for a digest that was fed incrementally with update
+    pub fn hash_digest(&self, digest: D) -> BigUint{
+        bits2int(&digest.finalize(), self.q.bits() as usize) % &self.q
+    }
+
+    // hashes the message with hash_message and signs it with deterministic nonces
+    pub fn sign_message(
+        &self,
+        message: &[u8],
+        priv_key: &PrivateKey
+    ) -> Result<Signature, Error> {
+        self.sign_deterministic(&self.hash_message(message), priv_key)
+    }
+
+    pub fn verify_message(
+        &self,
+        message: &[u8],
+        pub_key: &PublicKey,
+        signature: &Signature
+    ) -> Result<bool, Error> {
+        self.verify(&self.hash_message(message), pub_key, signature)
+    }
+
+    // streaming variants, for inputs that do not fit in memory:
+    // let mut digest = Sha256::new();
+    // digest.update(chunk); ...
+    // signature_scheme.sign_digest(digest, &priv_key)
+    pub fn sign_digest(
+        &self,
+        digest: D,
+        priv_key: &PrivateKey
+    ) -> Result<Signature, Error> {
+        self.sign_deterministic(&self.hash_digest(digest), priv_key)
+    }
+
+    pub fn verify_digest(
+        &self,
+        digest: D,
+        pub_key: &PublicKey,
+        signature: &Signature
+    ) -> Result<bool, Error> {
+        self.verify(&self.hash_digest(digest), pub_key, signature)
+    }
+
+    // signs everything the reader yields (a file, a socket, ...) in chunks of READ_BUFFER_SIZE,
+    // memory use does not depend on the size of the input
+    // the signature is the same as sign_message of the whole content
+    pub fn sign_reader<R: Read>(
+        &self,
+        reader: R,
+        priv_key: &PrivateKey
+    ) -> Result<Signature, Error> {
+        self.sign_digest(Self::digest_reader(reader)?, priv_key)
+    }
+
+    pub fn verify_reader<R: Read>(
+        &self,
+        reader: R,
+        pub_key: &PublicKey,
+        signature: &Signature
+    ) -> Result<bool, Error> {
+        self.verify_digest(Self::digest_reader(reader)?, pub_key, signature)
+    }
+
+    fn digest_reader<R: Read>(mut reader: R) -> Result<D, Error>{
+        let mut digest = D::new();
+        let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => return Ok(digest),
+                Ok(n) => digest.update(&buffer[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_secp256k1_sign_and_verify(){
+        let signature_scheme = crate::curves::secp256k1().signature_scheme();
+
+        let priv_key = signature_scheme.generate_private_key();
+        let pub_key = signature_scheme.generate_pub_key(&priv_key);
+        println!("PubKey B = {:?}\n", pub_key);
+
+        // creating a random hash, k_random and l_random in [1, q)
+        let q = signature_scheme.order();
+        let hash = signature_scheme.generate_random_number_in_range(q);
+        let k_random = signature_scheme.generate_nonce();
+        let l_random = signature_scheme.generate_nonce();
+
+        let signature = signature_scheme.sign(&hash, &priv_key, &k_random, &l_random).unwrap();
+        println!("signature = {:?}\n", signature);
+
+        let verify_result = signature_scheme.verify(&hash, &pub_key, &signature).unwrap();
+        assert!(verify_result, "verification should succeed");
+    }
+
+    #[test]
+    fn test_try_new(){
+        let toy757 = crate::curves::toy757();
+        let (ec, gen, q) = (toy757.curve, toy757.generator, toy757.order);
+
+        let signature_scheme = ElGamallikeSignature::try_new(ec.clone(), gen.clone(), q.clone()).unwrap();
+        let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+        let signature = signature_scheme.sign_message(b"hello world", &priv_key).unwrap();
+        assert!(signature_scheme.verify_message(b"hello world", &pub_key, &signature).unwrap());
+
+        let check = |ec: &EllipticCurve, gen: Point, q: u32| {
+            ElGamallikeSignature::<sha2::Sha512>::try_with_digest(ec.clone(), gen, BigUint::from(q)).err()
+        };
+        let invalid = |e: ParameterError| Some(Error::InvalidParameters(e));
+
+        let singular = EllipticCurve{ a: BigUint::from(0u32), b: BigUint::from(0u32), p: BigUint::from(757u32) };
+        assert_eq!(check(&singular, gen.clone(), 113), invalid(ParameterError::SingularCurve));
+        assert_eq!(check(&ec, Point::Identity, 113), invalid(ParameterError::IdentityGenerator));
+        let off_curve = Point::Coor(BigUint::from(529u32), BigUint::from(567u32));
+        assert_eq!(check(&ec, off_curve, 113), invalid(ParameterError::GeneratorNotOnCurve));
+        // (529 + 757, 566) satisfies the curve equation mod p but is not reduced
+        let unreduced = Point::Coor(BigUint::from(529u32 + 757), BigUint::from(566u32));
+        assert_eq!(check(&ec, unreduced, 113), invalid(ParameterError::GeneratorNotOnCurve));
+        // 791 = 7 * 113 is the number of points, 791G = O but 791 is not prime
+        assert_eq!(check(&ec, gen.clone(), 791), invalid(ParameterError::OrderNotPrime));
+        assert_eq!(check(&ec, gen.clone(), 109), invalid(ParameterError::WrongOrder));
+        assert_eq!(check(&ec, gen, 113), None);
+    }
+
+    #[test]
+    fn test_verify_rejects_small_order_components(){
+        let toy757 = crate::curves::toy757();
+        let signature_scheme = toy757.signature_scheme();
+        let ec = signature_scheme.curve().clone();
+
+        // T = 113 P has order 7 for any point P outside the subgroup of G
+        let torsion = (0u32..757)
+            .filter_map(|x| {
+                let x = BigUint::from(x);
+                let rhs = (x.pow(3) + BigUint::from(6u32) * &x + BigUint::from(2u32)) % &ec.p;
+                FiniteField::sqrt(&rhs, &ec.p).map(|y| Point::Coor(x, y))
+            })
+            .map(|point| ec.scalar_mul(&point, &toy757.order))
+            .find(|t| *t != Point::Identity)
+            .unwrap();
+        assert_eq!(ec.scalar_mul(&torsion, &BigUint::from(7u32)), Point::Identity);
+
+        let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+        let hash = signature_scheme.hash_message(b"hello world");
+        let signature = signature_scheme.sign_deterministic(&hash, &priv_key).unwrap();
+        assert!(signature_scheme.verify(&hash, &pub_key, &signature).unwrap());
+
+        // R' = R + T is on the curve, the batch used to accept it for one weight in 7
+        let forged = Signature {
+            r_point: ec.add(&signature.r_point, &torsion),
+            s_point: signature.s_point.clone(),
+            t: signature.t.clone(),
+        };
+        assert_eq!(signature_scheme.verify(&hash, &pub_key, &forged), Err(Error::PointNotInSubgroup));
+        for _ in 0..50 {
+            let batch = [(&hash, &pub_key, &signature), (&hash, &pub_key, &forged)];
+            assert_eq!(signature_scheme.verify_batch(&batch), Err(Error::InvalidSignatures(vec![1])));
+        }
+        assert_eq!(
+            Signature::new(forged.r_point.clone(), forged.s_point.clone(), forged.t.clone(), &signature_scheme),
+            Err(Error::PointNotInSubgroup)
+        );
+
+        let weak_key = ec.add(pub_key.point(), &torsion);
+        assert_eq!(PublicKey::new(weak_key, &signature_scheme), Err(Error::PointNotInSubgroup));
+    }
+
+    #[test]
+    fn test_sign(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        let pub_key = signature_scheme.generate_pub_key(&priv_key);
+        println!("PubKey B = {:?}", pub_key);
+
+        let hash = BigUint::from(56u32);
+        let k_random = Nonce::new(BigUint::from(81u32), &signature_scheme).unwrap();
+        let l_random = Nonce::new(BigUint::from(63u32), &signature_scheme).unwrap();
+
+        let signature = signature_scheme.sign(&hash, &priv_key, &k_random, &l_random).unwrap();
+        println!("{:?}", signature);
+    }
+
+    #[test]
+    fn test_verify(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        let pub_key = signature_scheme.generate_pub_key(&priv_key);
+
+        let hash = BigUint::from(56u32);
+        let k_random = Nonce::new(BigUint::from(81u32), &signature_scheme).unwrap();
+        let l_random = Nonce::new(BigUint::from(63u32), &signature_scheme).unwrap();
+
+        let signature = signature_scheme.sign(&hash, &priv_key, &k_random, &l_random).unwrap();
+        println!("{:?}", signature);
+
+        let verify_result = signature_scheme.verify(&hash, &pub_key, &signature).unwrap();
+        assert!(verify_result, "verification should succeed");
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_signature(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        let pub_key = signature_scheme.generate_pub_key(&priv_key);
+
+        let hash = BigUint::from(56u32);
+        let k_random = Nonce::new(BigUint::from(81u32), &signature_scheme).unwrap();
+        let l_random = Nonce::new(BigUint::from(63u32), &signature_scheme).unwrap();
+
+        let signature = signature_scheme.sign(&hash, &priv_key, &k_random, &l_random).unwrap();
+        let Signature { r_point, s_point, t } = signature.clone();
+
+        // malformed signatures can only be built inside the crate, Signature::new rejects them
+        let identity_r = Signature { r_point: Point::Identity, s_point: s_point.clone(), t: t.clone() };
+        assert_eq!(signature_scheme.verify(&hash, &pub_key, &identity_r), Err(Error::IdentityInSignature));
+
+        let off_curve_s = Signature {
+            r_point: r_point.clone(),
+            s_point: Point::Coor(BigUint::from(1u32), BigUint::from(1u32)),
+            t,
+        };
+        assert_eq!(signature_scheme.verify(&hash, &pub_key, &off_curve_s), Err(Error::PointNotOnCurve));
+
+        let big_t = Signature { r_point, s_point, t: BigUint::from(113u32) };
+        assert_eq!(signature_scheme.verify(&hash, &pub_key, &big_t), Err(Error::ScalarOutOfRange));
+
+        assert_eq!(signature_scheme.verify(&BigUint::from(113u32), &pub_key, &signature), Err(Error::ScalarOutOfRange));
+        let identity_key = PublicKey { point: Point::Identity };
+        assert_eq!(signature_scheme.verify(&hash, &identity_key, &signature), Err(Error::IdentityPublicKey));
+    }
+
+    #[test]
+    fn test_verify_rejects_unreduced_coordinates(){
+        let signature_scheme = crate::curves::secp256k1().signature_scheme();
+        let p = signature_scheme.curve().p.clone();
+
+        let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+        let hash = signature_scheme.hash_message(b"hello world");
+        let signature = signature_scheme.sign_deterministic(&hash, &priv_key).unwrap();
+
+        let unreduced_y = |point: &Point| match point {
+            Point::Coor(x, y) => Point::Coor(x.clone(), y + &p),
+            Point::Identity => unreachable!(),
+        };
+
+        // used to panic in negate
+        let forged = Signature {
+            r_point: unreduced_y(&signature.r_point),
+            s_point: signature.s_point.clone(),
+            t: signature.t.clone(),
+        };
+        assert_eq!(signature_scheme.verify(&hash, &pub_key, &forged), Err(Error::FieldElementOutOfRange));
+        let batch = [(&hash, &pub_key, &signature), (&hash, &pub_key, &forged)];
+        assert_eq!(signature_scheme.verify_batch(&batch), Err(Error::InvalidSignatures(vec![1])));
+
+        let forged_key = PublicKey { point: unreduced_y(&pub_key.point) };
+        assert_eq!(signature_scheme.verify(&hash, &forged_key, &signature), Err(Error::FieldElementOutOfRange));
+        assert_eq!(PublicKey::new(forged_key.point.clone(), &signature_scheme), Err(Error::FieldElementOutOfRange));
+        assert_eq!(
+            Signature::new(forged.r_point.clone(), forged.s_point.clone(), forged.t.clone(), &signature_scheme),
+            Err(Error::FieldElementOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_sign_rejects_out_of_range_scalars(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        let k_random = Nonce::new(BigUint::from(81u32), &signature_scheme).unwrap();
+        let l_random = Nonce::new(BigUint::from(63u32), &signature_scheme).unwrap();
+
+        let res = signature_scheme.sign(&BigUint::from(200u32), &priv_key, &k_random, &l_random);
+        assert_eq!(res, Err(Error::ScalarOutOfRange));
+
+        assert_eq!(Nonce::new(BigUint::from(0u32), &signature_scheme).unwrap_err(), Error::ScalarOutOfRange);
+        assert_eq!(Nonce::new(BigUint::from(113u32), &signature_scheme).unwrap_err(), Error::ScalarOutOfRange);
+
+        // a nonce of a scheme with a bigger order
+        let secp256k1_scheme = crate::curves::secp256k1().signature_scheme();
+        let big_nonce = Nonce::new(BigUint::from(200u32), &secp256k1_scheme).unwrap();
+        let res = signature_scheme.sign(&BigUint::from(56u32), &priv_key, &big_nonce, &l_random);
+        assert_eq!(res, Err(Error::ScalarOutOfRange));
+    }
+
+    #[test]
+    fn test_verify_batch(){
+        let signature_scheme = crate::curves::secp256k1().signature_scheme();
+
+        let mut hashes = Vec::new();
+        let mut pub_keys = Vec::new();
+        let mut signatures = Vec::new();
+        for _ in 0..100 {
+            let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+            let hash = signature_scheme.generate_random_number_in_range(signature_scheme.order());
+            let k_random = signature_scheme.generate_nonce();
+            let l_random = signature_scheme.generate_nonce();
+            let signature = signature_scheme.sign(&hash, &priv_key, &k_random, &l_random).unwrap();
+            hashes.push(hash);
+            pub_keys.push(pub_key);
+            signatures.push(signature);
+        }
+
+        // 1 + 3 * 100 points, above PIPPENGER_THRESHOLD
+        let large_batch: Vec<_> = (0..100).map(|i| (&hashes[i], &pub_keys[i], &signatures[i])).collect();
+        assert_eq!(signature_scheme.verify_batch(&large_batch), Ok(()));
+        let wrong_hash = &hashes[50] + BigUint::from(1u32);
+        let mut large_batch = large_batch;
+        large_batch[50].0 = &wrong_hash;
+        assert_eq!(signature_scheme.verify_batch(&large_batch), Err(Error::InvalidSignatures(vec![50])));
+
+        let batch: Vec<_> = (0..5).map(|i| (&hashes[i], &pub_keys[i], &signatures[i])).collect();
+        assert_eq!(signature_scheme.verify_batch(&batch), Ok(()));
+        assert_eq!(signature_scheme.verify_batch(&[]), Ok(()));
+
+        // signature 1 is signed for another message, signature 3 has an identity point
+        let wrong_hash = &hashes[1] + BigUint::from(1u32);
+        let malformed = Signature { r_point: Point::Identity, ..signatures[3].clone() };
+        let mut batch = batch;
+        batch[1].0 = &wrong_hash;
+        batch[3].2 = &malformed;
+        assert_eq!(signature_scheme.verify_batch(&batch), Err(Error::InvalidSignatures(vec![1, 3])));
+
+        // a swapped public key
+        let batch = vec![(&hashes[0], &pub_keys[0], &signatures[0]), (&hashes[2], &pub_keys[4], &signatures[2])];
+        assert_eq!(signature_scheme.verify_batch(&batch), Err(Error::InvalidSignatures(vec![1])));
+    }
+
+    #[test]
+    fn test_sign_deterministic(){
+        let signature_scheme = crate::curves::secp256k1().signature_scheme();
+
+        let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+        let hash = signature_scheme.generate_random_number_in_range(signature_scheme.order());
+
+        let signature = signature_scheme.sign_deterministic(&hash, &priv_key).unwrap();
+        assert!(signature_scheme.verify(&hash, &pub_key, &signature).unwrap());
+
+        // same input, same signature
+        assert_eq!(signature_scheme.sign_deterministic(&hash, &priv_key).unwrap(), signature);
+
+        // R = kG and S = lG come from independent nonces
+        assert_ne!(signature.r_point(), signature.s_point());
+
+        // another message gives other nonces
+        let other_hash = &hash + BigUint::from(1u32);
+        let other_signature = signature_scheme.sign_deterministic(&other_hash, &priv_key).unwrap();
+        assert_ne!(other_signature.r_point(), signature.r_point());
+        assert_ne!(other_signature.s_point(), signature.s_point());
+        assert!(signature_scheme.verify(&other_hash, &pub_key, &other_signature).unwrap());
+    }
+
+    #[test]
+    fn test_hash_message(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        // SHA-256("abc") = ba7816bf..., q has 7 bits so e = 0xba >> 1 = 93 and m = 93 mod 113
+        assert_eq!(signature_scheme.hash_message(b"abc"), BigUint::from(93u32));
+    }
+
+    #[test]
+    fn test_sign_and_verify_message(){
+        let signature_scheme = crate::curves::secp256k1().signature_scheme();
+
+        // SHA-256("abc") is smaller than the secp256k1 order, so m is the digest itself
+        let abc = BigUint::parse_bytes(
+            b"BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD",
+            16
+        ).unwrap();
+        assert_eq!(signature_scheme.hash_message(b"abc"), abc);
+
+        let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+        let signature = signature_scheme.sign_message(b"hello world", &priv_key).unwrap();
+
+        assert!(signature_scheme.verify_message(b"hello world", &pub_key, &signature).unwrap());
+        assert!(!signature_scheme.verify_message(b"hello world!", &pub_key, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_pluggable_digests(){
+        let secp256k1 = crate::curves::secp256k1();
+        let ec = secp256k1.curve;
+        let q = secp256k1.order;
+        let gen = secp256k1.generator;
+
+        let sha512_scheme = ElGamallikeSignature::<sha2::Sha512>::with_digest(ec.clone(), gen.clone(), q.clone());
+        let sha3_scheme = ElGamallikeSignature::<sha3::Sha3_256>::with_digest(ec, gen, q.clone());
+
+        // SHA-512 digests are truncated to their leftmost 256 bits
+        let digest = sha2::Sha512::digest(b"abc");
+        let expected = BigUint::from_bytes_be(&digest[..32]) % &q;
+        assert_eq!(sha512_scheme.hash_message(b"abc"), expected);
+
+        assert!(sign_and_verify_with(&sha512_scheme));
+        assert!(sign_and_verify_with(&sha3_scheme));
+    }
+
+    fn sign_and_verify_with<D: Digest + BlockSizeUser>(signature_scheme: &ElGamallikeSignature<D>) -> bool{
+        let (priv_key, pub_key) = signature_scheme.generate_key_pair();
+        let signature = signature_scheme.sign_message(b"hello world", &priv_key).unwrap();
+        signature_scheme.verify_message(b"hello world", &pub_key, &signature).unwrap()
+            && !signature_scheme.verify_message(b"hello world!", &pub_key, &signature).unwrap()
+    }
+
+    #[test]
+    fn test_sign_digest_streaming(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        let pub_key = signature_scheme.generate_pub_key(&priv_key);
+
+        let mut digest = Sha256::new();
+        for chunk in [&b"hello"[..], b" ", b"world"] {
+            digest.update(chunk);
+        }
+        let signature = signature_scheme.sign_digest(digest, &priv_key).unwrap();
+
+        // the streamed signature is the one of the whole message
+        assert_eq!(signature, signature_scheme.sign_message(b"hello world", &priv_key).unwrap());
+        let digest = Sha256::new().chain_update(b"hello ").chain_update(b"world");
+        assert!(signature_scheme.verify_digest(digest, &pub_key, &signature).unwrap());
+    }
+
+    // yields the data a few bytes at a time, or fails after the first chunk
+    struct ChunkedReader{
+        data: Vec<u8>,
+        position: usize,
+        fail: bool,
+    }
+
+    impl Read for ChunkedReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize>{
+            if self.fail && self.position > 0 {
+                return Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "broken"));
+            }
+            let n = std::cmp::min(7, std::cmp::min(buf.len(), self.data.len() - self.position));
+            buf[..n].copy_from_slice(&self.data[self.position..self.position + n]);
+            self.position += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_sign_reader(){
+        let signature_scheme = crate::curves::toy757().signature_scheme();
+
+        let priv_key = PrivateKey::new(BigUint::from(78u32), &signature_scheme).unwrap();
+        let pub_key = signature_scheme.generate_pub_key(&priv_key);
+
+        // bigger than READ_BUFFER_SIZE so it takes several reads
+        let data: Vec<u8> = (0..3 * READ_BUFFER_SIZE + 5).map(|i| (i % 251) as u8).collect();
+
+        let signature = signature_scheme.sign_reader(std::io::Cursor::new(&data), &priv_key).unwrap();
+        assert_eq!(signature, signature_scheme.sign_message(&data, &priv_key).unwrap());
+        assert!(signature_scheme.verify_reader(std::io::Cursor::new(&data), &pub_key, &signature).unwrap());
+
+        let chunked = ChunkedReader { data: data.clone(), position: 0, fail: false };
+        assert!(signature_scheme.verify_reader(chunked, &pub_key, &signature).unwrap());
+
+        let broken = ChunkedReader { data, position: 0, fail: true };
+        let res = signature_scheme.sign_reader(broken, &priv_key);
+        assert_eq!(res, Err(Error::Io(std::io::ErrorKind::BrokenPipe)));
+    }
+}
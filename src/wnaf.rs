@@ -0,0 +1,160 @@
+use crate::jacobian::JacobianPoint;
+use crate::{EllipticCurve, Error, FiniteField, Point};
+use num_bigint::BigUint;
+
+// window width used by the verifier
+pub(crate) const DEFAULT_WINDOW: usize = 4;
+
+// widths outside this range either degenerate to double-and-add or need huge tables
+const MIN_WINDOW: usize = 2;
+const MAX_WINDOW: usize = 16;
+
+// width-w non-adjacent form of the exponent, least significant digit first
+// every non-zero digit is odd with |d| < 2^(w-1) and any w consecutive digits contain at most one non-zero
+pub(crate) fn wnaf(exponent: &BigUint, w: usize) -> Vec<i64>{
+    let window = 1i64 << w;
+    let half_window = 1i64 << (w - 1);
+    let mask = BigUint::from((window - 1) as u64);
+    let mut k = exponent.clone();
+    let mut digits = Vec::with_capacity(exponent.bits() as usize + 1);
+    while k != BigUint::from(0u32) {
+        let mut digit = 0i64;
+        if k.bit(0) {
+            // k mod 2^w, mapped into (-2^(w-1), 2^(w-1))
+            digit = (&k & &mask).iter_u64_digits().next().unwrap_or(0) as i64;
+            if digit >= half_window {
+                digit -= window;
+            }
+            if digit > 0 {
+                k -= BigUint::from(digit as u64);
+            } else {
+                k += BigUint::from((-digit) as u64);
+            }
+        }
+        digits.push(digit);
+        k >>= 1;
+    }
+    digits
+}
+
+impl EllipticCurve {
+    pub(crate) fn jacobian_negate(&self, c: &JacobianPoint) -> JacobianPoint{
+        // -(X, Y, Z) = (X, -Y, Z), negation is free compared to an addition
+        JacobianPoint {
+            x: c.x.clone(),
+            y: FiniteField::subs(&BigUint::from(0u32), &c.y, &self.p),
+            z: c.z.clone(),
+        }
+    }
+
+    // odd multiples c, 3c, 5c, ..., (2^(w-1) - 1)c
+    pub(crate) fn wnaf_table(&self, c: &JacobianPoint, w: usize) -> Vec<JacobianPoint>{
+        let size = 1usize << (w - 2);
+        let two_c = self.jacobian_double(c);
+        let mut table = Vec::with_capacity(size);
+        table.push(c.clone());
+        for i in 1..size {
+            let next = self.jacobian_add(&table[i - 1], &two_c);
+            table.push(next);
+        }
+        table
+    }
+
+    // the digit d is odd, table[(|d| - 1) / 2] = |d| * c
+    pub(crate) fn wnaf_lookup(&self, table: &[JacobianPoint], digit: i64) -> JacobianPoint{
+        let entry = &table[(digit.unsigned_abs() as usize - 1) / 2];
+        if digit > 0 {
+            entry.clone()
+        } else {
+            self.jacobian_negate(entry)
+        }
+    }
+
+    pub(crate) fn scalar_mul_wnaf_jacobian(&self, c: &JacobianPoint, exponent: &BigUint, w: usize) -> JacobianPoint{
+        let table = self.wnaf_table(c, w);
+        let mut t = JacobianPoint::identity();
+        for &digit in wnaf(exponent, w).iter().rev() {
+            t = self.jacobian_double(&t);
+            if digit != 0 {
+                t = self.jacobian_add(&t, &self.wnaf_lookup(&table, digit));
+            }
+        }
+        t
+    }
+
+    // variable-time w-NAF scalar multiplication, only for public scalars
+    // w = 2 is the classic NAF, bigger windows trade a table of 2^(w-2) points for fewer additions
+    pub fn scalar_mul_wnaf(&self, c: &Point, exponent: &BigUint, w: usize) -> Point{
+        self.try_scalar_mul_wnaf(c, exponent, w).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_scalar_mul_wnaf(&self, c: &Point, exponent: &BigUint, w: usize) -> Result<Point, Error>{
+        if !(MIN_WINDOW..=MAX_WINDOW).contains(&w) {
+            return Err(Error::InvalidWindowSize);
+        }
+        if !self.is_on_curve(c) {
+            return Err(Error::PointNotOnCurve);
+        }
+        let t = self.scalar_mul_wnaf_jacobian(&self.to_jacobian(c), exponent, w);
+        Ok(self.to_affine(&t))
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn test_wnaf_digits(){
+        // 7 = 8 - 1 => NAF (w = 2) is [-1, 0, 0, 1]
+        assert_eq!(wnaf(&BigUint::from(7u32), 2), vec![-1, 0, 0, 1]);
+        assert_eq!(wnaf(&BigUint::from(0u32), 4), Vec::<i64>::new());
+
+        for w in 2..7 {
+            for k in 0u32..300 {
+                let digits = wnaf(&BigUint::from(k), w);
+                let mut value = 0i64;
+                for (i, d) in digits.iter().enumerate() {
+                    assert!(*d == 0 || (d % 2 != 0 && d.abs() < 1 << (w - 1)));
+                    value += d << i;
+                }
+                assert_eq!(value, k as i64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scalar_mul_wnaf(){
+        //y^2 = x^3 + 2x + 2 mod 17, the order of the group is 19
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let p = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+
+        for w in 2..6 {
+            for k in 0u32..60 {
+                let k = BigUint::from(k);
+                assert_eq!(ec.scalar_mul_wnaf(&p, &k, w), ec.scalar_mul(&p, &k));
+            }
+            assert_eq!(ec.scalar_mul_wnaf(&Point::Identity, &BigUint::from(5u32), w), Point::Identity);
+        }
+    }
+
+    #[test]
+    fn test_scalar_mul_wnaf_invalid_window(){
+        //y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve{
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let p = Point::Coor(BigUint::from(5u32), BigUint::from(1u32));
+
+        assert_eq!(ec.try_scalar_mul_wnaf(&p, &BigUint::from(3u32), 1), Err(Error::InvalidWindowSize));
+        assert_eq!(ec.try_scalar_mul_wnaf(&p, &BigUint::from(3u32), 17), Err(Error::InvalidWindowSize));
+    }
+}